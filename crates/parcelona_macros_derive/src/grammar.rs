@@ -0,0 +1,335 @@
+//! `grammar!{}` PEG-like rules, expands to calls into `parcelona::parser_combinators`
+//!
+//! ```text
+//! type Input = u8;                          // optional, default u8
+//! pub name -> Type = expression ;
+//!
+//! e1 e2          sequence, result is a tuple (O1,O2)
+//! e1 / e2        ordered choice
+//! e* e+ e?       repetitions and option
+//! &e !e          positive and negative lookahead
+//! $( e )         capture, result is the consumed slice
+//! x:e ... => { } action, labeled results are bound in the block
+//! "abc" b"abc"   literal
+//! [predicate]    one element satisfying predicate, e.g. [is_dec_digit]
+//! .              any one element
+//! rule           other rule or any parser in scope
+//! { expr }       any parser expression
+//! ```
+//!
+//! Choices and sequences have up to 16 elements, 32 or 64 with features `alt_tuple_32` or `alt_tuple_64`.
+//! Left recursion, direct or through other rules, and `e*` or `e+` of `e` matching empty input are errors.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, parenthesized, Block, Ident, LitByteStr, LitStr, Token, Type, Visibility};
+use std::collections::HashMap;
+
+/// max elements of tuple implemented for `Seq` and `Alt`, the same features as of `parcelona`
+#[cfg(feature = "alt_tuple_32")]
+const TUPLE_MAX: usize = 32;
+#[cfg(all(feature = "alt_tuple_64", not(feature = "alt_tuple_32")))]
+const TUPLE_MAX: usize = 64;
+#[cfg(not(any(feature = "alt_tuple_32", feature = "alt_tuple_64")))]
+const TUPLE_MAX: usize = 16;
+
+pub struct Grammar {
+    input: Type,
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    vis: Visibility,
+    name: Ident,
+    ty: Type,
+    expr: Expr,
+}
+
+enum Expr {
+    Choice(Vec<Expr>),
+    Seq { items: Vec<(Option<Ident>, Expr)>, action: Option<Block>, span: Span },
+    Lit(LitByteStr),
+    Any(Span),
+    Class(syn::Expr),
+    Ref(Ident),
+    Embed(syn::Expr),
+    Capture(Box<Expr>),
+    Many0(Box<Expr>, Span),
+    Many1(Box<Expr>, Span),
+    Opt(Box<Expr>),
+    Peek(Box<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Parse for Grammar {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut r = Grammar { input: syn::parse_quote!(u8), rules: Vec::new() };
+        if input.peek(Token![type]) {
+            input.parse::<Token![type]>()?;
+            let name: Ident = input.parse()?;
+            if name != "Input" { return Err(syn::Error::new(name.span(), "expected `type Input = ...;`")); }
+            input.parse::<Token![=]>()?;
+            r.input = input.parse()?;
+            input.parse::<Token![;]>()?;
+        }
+        while !input.is_empty() {
+            let rule: Rule = input.parse()?;
+            if r.rules.iter().any(|x| x.name == rule.name) {
+                return Err(syn::Error::new(rule.name.span(), format!("rule `{}` is defined more than once", rule.name)));
+            }
+            r.rules.push(rule);
+        }
+        Ok(r)
+    }
+}
+
+impl Parse for Rule {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let vis = input.parse()?;
+        let name = input.parse()?;
+        input.parse::<Token![->]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let expr = parse_choice(input)?;
+        input.parse::<Token![;]>()?;
+        Ok(Rule { vis, name, ty, expr })
+    }
+}
+
+fn parse_choice(input: ParseStream) -> syn::Result<Expr> {
+    let span = input.span();
+    let mut alts = vec![parse_seq(input)?];
+    while input.peek(Token![/]) {
+        input.parse::<Token![/]>()?;
+        alts.push(parse_seq(input)?);
+    }
+    if alts.len() > TUPLE_MAX {
+        return Err(syn::Error::new(span, format!("choice has more than {} alternatives, split it into rules", TUPLE_MAX)));
+    }
+    if alts.len() == 1 { Ok(alts.pop().unwrap()) } else { Ok(Expr::Choice(alts)) }
+}
+
+fn seq_end(input: ParseStream) -> bool {
+    input.is_empty() || input.peek(Token![/]) || input.peek(Token![;]) || input.peek(Token![=>])
+}
+
+fn parse_seq(input: ParseStream) -> syn::Result<Expr> {
+    let span = input.span();
+    let mut items = Vec::new();
+    while !seq_end(input) {
+        let mut label = None;
+        if input.peek(Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+            label = Some(input.parse::<Ident>()?);
+            input.parse::<Token![:]>()?;
+        }
+        items.push((label, parse_prefix(input)?));
+    }
+    if items.is_empty() { return Err(syn::Error::new(span, "empty sequence")); }
+    let mut action = None;
+    if input.peek(Token![=>]) {
+        input.parse::<Token![=>]>()?;
+        action = Some(input.parse::<Block>()?);
+    }
+    if action.is_none() {
+        if let Some(l) = items.iter().find_map(|x| x.0.as_ref()) {
+            return Err(syn::Error::new(l.span(), format!("label `{}` is unused, add an action `=> {{ ... }}`", l)));
+        }
+        if items.len() == 1 { return Ok(items.pop().unwrap().1); }
+    }
+    if items.len() > TUPLE_MAX {
+        return Err(syn::Error::new(span, format!("sequence is longer than {} elements, split it into rules", TUPLE_MAX)));
+    }
+    Ok(Expr::Seq { items, action, span })
+}
+
+fn parse_prefix(input: ParseStream) -> syn::Result<Expr> {
+    if input.peek(Token![&]) {
+        input.parse::<Token![&]>()?;
+        return Ok(Expr::Peek(Box::new(parse_prefix(input)?)));
+    }
+    if input.peek(Token![!]) {
+        input.parse::<Token![!]>()?;
+        return Ok(Expr::Not(Box::new(parse_prefix(input)?)));
+    }
+    let mut e = parse_primary(input)?;
+    loop {
+        if input.peek(Token![*]) { let t = input.parse::<Token![*]>()?; e = Expr::Many0(Box::new(e), t.span); }
+        else if input.peek(Token![+]) { let t = input.parse::<Token![+]>()?; e = Expr::Many1(Box::new(e), t.span); }
+        else if input.peek(Token![?]) { input.parse::<Token![?]>()?; e = Expr::Opt(Box::new(e)); }
+        else { break; }
+    }
+    Ok(e)
+}
+
+fn parse_primary(input: ParseStream) -> syn::Result<Expr> {
+    if input.peek(LitStr) {
+        let l: LitStr = input.parse()?;
+        return Ok(Expr::Lit(LitByteStr::new(l.value().as_bytes(), l.span())));
+    }
+    if input.peek(LitByteStr) { return Ok(Expr::Lit(input.parse()?)); }
+    if input.peek(Token![.]) { return Ok(Expr::Any(input.parse::<Token![.]>()?.span)); }
+    if input.peek(Token![$]) {
+        input.parse::<Token![$]>()?;
+        let content;
+        parenthesized!(content in input);
+        let e = parse_choice(&content)?;
+        if !content.is_empty() { return Err(content.error("unexpected token in capture")); }
+        return Ok(Expr::Capture(Box::new(e)));
+    }
+    if input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let e = parse_choice(&content)?;
+        if !content.is_empty() { return Err(content.error("unexpected token in group")); }
+        return Ok(e);
+    }
+    if input.peek(syn::token::Bracket) {
+        let content;
+        bracketed!(content in input);
+        return Ok(Expr::Class(content.parse()?));
+    }
+    if input.peek(syn::token::Brace) {
+        let content;
+        braced!(content in input);
+        return Ok(Expr::Embed(content.parse()?));
+    }
+    if input.peek(Ident) { return Ok(Expr::Ref(input.parse()?)); }
+    Err(input.error("expected grammar expression"))
+}
+
+/// rule names of the grammar and which of them match empty input
+struct Rules<'g> {
+    index: HashMap<String, usize>,
+    nullable: Vec<bool>,
+    rules: &'g [Rule],
+}
+
+impl<'g> Rules<'g> {
+    fn new(rules: &'g [Rule]) -> Self {
+        let index = rules.iter().enumerate().map(|(k, x)| (x.name.to_string(), k)).collect();
+        let mut r = Rules { index, nullable: vec![false; rules.len()], rules };
+        loop {
+            let next: Vec<bool> = rules.iter().map(|x| r.is_nullable(&x.expr)).collect();
+            if next == r.nullable { break; }
+            r.nullable = next;
+        }
+        r
+    }
+
+    /// expression can match empty input, parsers out of the grammar are taken as not
+    fn is_nullable(&self, e: &Expr) -> bool {
+        match e {
+            Expr::Choice(v) => v.iter().any(|x| self.is_nullable(x)),
+            Expr::Seq { items, .. } => items.iter().all(|x| self.is_nullable(&x.1)),
+            Expr::Lit(l) => l.value().is_empty(),
+            Expr::Ref(i) => self.index.get(&i.to_string()).is_some_and(|k| self.nullable[*k]),
+            Expr::Capture(x) | Expr::Many1(x, _) => self.is_nullable(x),
+            Expr::Many0(..) | Expr::Opt(_) | Expr::Peek(_) | Expr::Not(_) => true,
+            Expr::Any(_) | Expr::Class(_) | Expr::Embed(_) => false,
+        }
+    }
+
+    /// rules which can be called at the same position of input
+    fn leftmost(&self, e: &Expr, r: &mut Vec<usize>) {
+        match e {
+            Expr::Choice(v) => { for x in v { self.leftmost(x, r); } },
+            Expr::Seq { items, .. } => for x in items {
+                self.leftmost(&x.1, r);
+                if !self.is_nullable(&x.1) { break; }
+            },
+            Expr::Ref(i) => r.extend(self.index.get(&i.to_string())),
+            Expr::Capture(x) | Expr::Many0(x, _) | Expr::Many1(x, _) | Expr::Opt(x) | Expr::Peek(x) | Expr::Not(x) => self.leftmost(x, r),
+            _ => {},
+        }
+    }
+
+    /// repetitions of expressions matching empty input, they never end
+    fn empty_loops(&self, e: &Expr, r: &mut Vec<Span>) {
+        match e {
+            Expr::Choice(v) => { for x in v { self.empty_loops(x, r); } },
+            Expr::Seq { items, .. } => { for x in items { self.empty_loops(&x.1, r); } },
+            Expr::Many0(x, s) | Expr::Many1(x, s) => {
+                if self.is_nullable(x) { r.push(*s); }
+                self.empty_loops(x, r);
+            },
+            Expr::Capture(x) | Expr::Opt(x) | Expr::Peek(x) | Expr::Not(x) => self.empty_loops(x, r),
+            _ => {},
+        }
+    }
+
+    /// errors of left recursion, direct or through other rules, and of repetitions of empty matches
+    fn check(&self) -> syn::Result<()> {
+        let mut errors = Vec::new();
+        let edges: Vec<Vec<usize>> = self.rules.iter().map(|x| { let mut r = Vec::new(); self.leftmost(&x.expr, &mut r); r }).collect();
+        for (start, rule) in self.rules.iter().enumerate() {
+            let mut seen = vec![false; self.rules.len()];
+            let mut stack = edges[start].clone();
+            while let Some(i) = stack.pop() {
+                if i == start {
+                    errors.push(syn::Error::new(rule.name.span(), format!("rule `{}` is left recursive", rule.name)));
+                    break;
+                }
+                if !seen[i] { seen[i] = true; stack.extend(&edges[i]); }
+            }
+            let mut loops = Vec::new();
+            self.empty_loops(&rule.expr, &mut loops);
+            errors.extend(loops.into_iter().map(|s| syn::Error::new(s, "repetition of an expression that can match empty input")));
+        }
+        let mut errors = errors.into_iter();
+        match errors.next() {
+            None => Ok(()),
+            Some(mut e) => { e.extend(errors); Err(e) },
+        }
+    }
+}
+
+fn gen_expr(e: &Expr) -> syn::Result<TokenStream> {
+    let pc = quote!(::parcelona::parser_combinators);
+    Ok(match e {
+        Expr::Choice(v) => {
+            let v = v.iter().map(gen_expr).collect::<syn::Result<Vec<_>>>()?;
+            quote!(#pc::alt((#(#v),*)))
+        },
+        Expr::Seq { items, action, span } => {
+            let p = items.iter().map(|x| gen_expr(&x.1)).collect::<syn::Result<Vec<_>>>()?;
+            let labels: Vec<TokenStream> = items.iter()
+                .map(|x| match &x.0 { Some(l) => quote!(#l), None => quote!(_) })
+                .collect();
+            let seq = if p.len() == 1 { p[0].clone() } else { quote!(#pc::tuple((#(#p),*))) };
+            match action {
+                None => seq,
+                Some(b) if labels.len() == 1 => { let l = &labels[0]; quote_spanned!(*span=> #pc::fmap(#seq, |#l| #b)) },
+                Some(b) => quote_spanned!(*span=> #pc::fmap(#seq, |(#(#labels),*)| #b)),
+            }
+        },
+        Expr::Lit(l) => quote!(#pc::starts_with(#l)),
+        Expr::Any(s) => quote_spanned!(*s=> #pc::take(1)),
+        Expr::Class(p) => quote!(#pc::seq_exact(#p, 1)),
+        Expr::Ref(i) => quote!(#i),
+        Expr::Embed(p) => quote!((#p)),
+        Expr::Capture(x) => { let x = gen_expr(x)?; quote!(#pc::recognize(#x)) },
+        Expr::Many0(x, _) => { let x = gen_expr(x)?; quote!(#pc::more_min(#x, 0)) },
+        Expr::Many1(x, _) => { let x = gen_expr(x)?; quote!(#pc::more(#x)) },
+        Expr::Opt(x) => { let x = gen_expr(x)?; quote!(#pc::option(#x)) },
+        Expr::Peek(x) => { let x = gen_expr(x)?; quote!(#pc::peek(#x)) },
+        Expr::Not(x) => { let x = gen_expr(x)?; quote!(#pc::not(#x)) },
+    })
+}
+
+pub fn expand(g: Grammar) -> syn::Result<TokenStream> {
+    let mut gen = TokenStream::new();
+    let input = &g.input;
+    Rules::new(&g.rules).check()?;
+    for rule in &g.rules {
+        let Rule { vis, name, ty, expr } = rule;
+        let body = gen_expr(expr)?;
+        gen.extend(quote! {
+            #vis fn #name<'a>(input: &'a [#input]) -> ::parcelona::parser_combinators::ParseResult<'a, #input, #ty> {
+                use ::parcelona::parser_combinators::Parser as _;
+                (#body).parse(input)
+            }
+        });
+    }
+    Ok(gen)
+}
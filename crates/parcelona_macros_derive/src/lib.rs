@@ -2,6 +2,8 @@ use proc_macro2::{Ident, Span, TokenStream, Literal};
 use quote::{quote, TokenStreamExt};
 use syn::{parse_macro_input, Expr, LitInt};
 
+mod grammar;
+
 /// helper function
 /// example:
 /// call:    fn_alt_body(3);
@@ -260,4 +262,85 @@ pub fn permut_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
     //-shugar-// gen.into()
     proc_macro::TokenStream::from(gen)
-}
\ No newline at end of file
+}
+
+
+/*
+// This is what we want to get using macro
+impl<'a,I:'a,P0,O0,P1,O1> Seq<'a,I,(O0,O1)> for (P0,P1)
+where
+    P0: Parser<'a,I,O0>,
+    P1: Parser<'a,I,O1>,
+{
+    fn sequence(&self, input: &'a[I]) -> ParseResult<'a,I,(O0,O1)> {
+        let (input, r0) = self.0.parse(input)?;
+        let (input, r1) = self.1.parse(input)?;
+        Ok((input, (r0, r1)))
+    }
+}
+*/
+/// seq_impl!(90); max val 255 elemets tuple (A, B, ...)
+#[proc_macro]
+pub fn seq_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let i = parse_macro_input!(input as LitInt);
+    let value_max = i.base10_parse::<u8>().unwrap()+1;
+    if value_max < 2 { return Default::default(); }
+    let mut gen = TokenStream::new();
+
+    let for_vec_p: Vec<Ident> = (0..=value_max)
+        .into_iter()
+        .map(|a| Ident::new(&("P".to_owned()+&a.to_string()), Span::call_site()))
+        .collect();
+
+    let for_vec_o: Vec<Ident> = (0..=value_max)
+        .into_iter()
+        .map(|a| Ident::new(&("O".to_owned()+&a.to_string()), Span::call_site()))
+        .collect();
+
+    let for_vec_r: Vec<Ident> = (0..=value_max)
+        .into_iter()
+        .map(|a| Ident::new(&("r".to_owned()+&a.to_string()), Span::call_site()))
+        .collect();
+
+    let for_num: Vec<Literal> = (0..=value_max)
+        .into_iter()
+        .map(|a| Literal::usize_unsuffixed(a.into()))
+        .collect();
+
+    for value in 2..value_max as usize {
+        let vec_o = &for_vec_o[..value];
+        let vec_p = &for_vec_p[..value];
+        let vec_r = &for_vec_r[..value];
+        let num = &for_num[..value];
+
+        let gen_part = quote! {
+            impl<'a,I:'a,#(#vec_p,#vec_o),*> Seq<'a,I,(#(#vec_o),*)> for (#(#vec_p),*)
+            where
+            #(#vec_p: Parser<'a,I,#vec_o>),*
+            {
+                fn sequence(&self, input: &'a[I]) -> ParseResult<'a,I,(#(#vec_o),*)> {
+                    #(let (input, #vec_r) = self.#num.parse(input)?;)*
+                    Ok((input, (#(#vec_r),*)))
+                }
+            }
+        };
+
+        gen.append_all(gen_part);
+    }
+
+    //-shugar-// gen.into()
+    proc_macro::TokenStream::from(gen)
+}
+
+
+
+/// grammar!{ rules } PEG-like rules, every rule expands into a parser function,
+/// see syntax in `grammar.rs`
+#[proc_macro]
+pub fn grammar(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let g = parse_macro_input!(input as grammar::Grammar);
+    match grammar::expand(g) {
+        Ok(r)  => r.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -40,7 +40,7 @@ static TEXT: StaticClassOfSymbols<u8> = StaticClassOfSymbols::<u8>::new()
 	.parts_enable_set(&[br#"\\"#, br#"\<"#, br#"\>"#])
 	.default_enable_one(true); // if iten of slice is not disable then is enable
 
-fn parse_tag(input: &[u8]) -> Result<Tag, Box<dyn Error + '_>> {
+fn parse_tag(source: &[u8]) -> Result<Tag<'_>, Box<dyn Error + Send + Sync>> {
 
 	let mut name: ClassOfSymbols<u8> = Default::default();
 	name.range_enable_push(ALPHA_NUM)
@@ -66,14 +66,18 @@ fn parse_tag(input: &[u8]) -> Result<Tag, Box<dyn Error + '_>> {
 	let (input, (tag_name, tag_attrs)) = between(open, pair(name_parser, attrs), close)
 		.msg_err("first line pars eror")
 		.strerr()
-		.parse(input)?;
+		.parse(source)
+		.map_err(|e| e.into_owned(source))?;
 
-	let (input, tag_text) = fmap(TEXT.msg_err("text parse error").strerr(), <[u8]>::trim_ascii).parse(input)?;
+	let (input, tag_text) = fmap(TEXT.msg_err("text parse error").strerr(), <[u8]>::trim_ascii)
+		.parse(input)
+		.map_err(|e| e.into_owned(source))?;
 
 	let _ = between(open, pair(any(b"/"), starts_with(tag_name)), close)
 		.msg_err(END_TAG_NOTFOUND)
 		.strerr()
-		.parse(input)?;
+		.parse(input)
+		.map_err(|e| e.into_owned(source))?;
 
 	Ok(Tag {
 		name: from_utf8(tag_name)?,
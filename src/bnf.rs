@@ -0,0 +1,475 @@
+//! Runtime grammars.
+//! Loads grammar text in ABNF (RFC 5234 with RFC 7405 `%s`/`%i`) or EBNF (W3C XML notation)
+//! and builds a parser tree of parcelona primitives: `starts_with` for literals,
+//! `ClassOfSymbols` for ranges and classes, `more_range` for `n*m` repetitions.
+//! Choice is ordered as in PEG, the first matching alternative wins.
+//! Terminals are bytes: ABNF num-val above `%xFF` and EBNF code point above `#xFF` are not supported,
+//! neither is ABNF prose-val.
+//! On failure every rule on the way pushes ``rule `name` failed`` message to the error.
+
+use crate::parser_combinators::{*};
+use crate::u8::{*};
+use std::str::from_utf8;
+
+const ABNF_CORE: &[u8] = b"\
+ALPHA  = %x41-5A / %x61-7A
+BIT    = \"0\" / \"1\"
+CHAR   = %x01-7F
+CR     = %x0D
+CRLF   = CR LF
+CTL    = %x00-1F / %x7F
+DIGIT  = %x30-39
+DQUOTE = %x22
+HEXDIG = DIGIT / \"A\" / \"B\" / \"C\" / \"D\" / \"E\" / \"F\"
+HTAB   = %x09
+LF     = %x0A
+LWSP   = *(WSP / CRLF WSP)
+OCTET  = %x00-FF
+SP     = %x20
+VCHAR  = %x21-7E
+WSP    = SP / HTAB
+";
+
+#[derive(Debug,Clone)]
+enum Node {
+    /// literal, `true` if case insensitive
+    Lit(Vec<u8>, bool),
+    /// one element of the class
+    Class(ClassOfSymbols<u8>),
+    Seq(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat(usize, usize, Box<Node>),
+    /// first matches if second does not match
+    Except(Box<Node>, Box<Node>),
+    /// rule name and length of grammar text from it, before resolving
+    Ref(String, usize),
+    Rule(usize),
+}
+
+/// Grammar loaded at runtime
+#[derive(Debug,Clone,Default)]
+pub struct Grammar {
+    names: Vec<String>,
+    rules: Vec<Node>,
+    /// ABNF rule names are case insensitive
+    ignore_case: bool,
+}
+
+/// Parser of a grammar rule, returns the matched slice
+#[derive(Debug,Clone,Copy)]
+pub struct RuleParser<'g> {
+    g: &'g Grammar,
+    idx: usize,
+}
+
+impl<'a,'g:'a> Parser<'a,u8,&'a[u8]> for RuleParser<'g> {
+    fn parse(&self, input:&'a [u8]) -> ParseResult<'a,u8,&'a[u8]> {
+        recognize(move |i| self.g.eval_rule(self.idx, i)).parse(input)
+    }
+}
+
+impl Grammar {
+    /// load ABNF grammar, core rules (ALPHA, DIGIT, ...) are added if not defined
+    pub fn from_abnf(text:&[u8]) -> Result<Grammar, ParseError> {
+        let mut rules = abnf_rulelist(text)?;
+        let core = abnf_rulelist(ABNF_CORE)?;
+        for (name, node) in core {
+            if !rules.iter().any(|x| x.0.eq_ignore_ascii_case(&name)) { rules.push((name, node)); }
+        }
+        Grammar::build(rules, true, text)
+    }
+
+    /// load EBNF grammar in W3C XML notation: `name ::= expr`
+    pub fn from_ebnf(text:&[u8]) -> Result<Grammar, ParseError> {
+        let rules = ebnf_rulelist(text)?;
+        Grammar::build(rules, false, text)
+    }
+
+    /// parser of the rule
+    pub fn rule(&self, name:&str) -> Option<RuleParser<'_>> {
+        self.index(name).map(|idx| RuleParser { g: self, idx })
+    }
+
+    /// names of all rules
+    pub fn rule_names(&self) -> &[String] { &self.names }
+
+    fn index(&self, name:&str) -> Option<usize> {
+        if self.ignore_case { self.names.iter().position(|x| x.eq_ignore_ascii_case(name)) }
+        else { self.names.iter().position(|x| x == name) }
+    }
+
+    fn build(rules: Vec<(String, Node)>, ignore_case: bool, text:&[u8]) -> Result<Grammar, ParseError> {
+        let mut g = Grammar { ignore_case, ..Default::default() };
+        let mut nodes = Vec::new();
+        for (name, node) in rules {
+            g.names.push(name);
+            nodes.push(node);
+        }
+        for mut node in nodes {
+            g.resolve(&mut node).map_err(|(msg, rest)| {
+                let at = &text[text.len().saturating_sub(rest)..];
+                PErr::new(at).user_msg_push(Msg::String(msg)).fmt_str().into_owned(text)
+            })?;
+            g.rules.push(node);
+        }
+        g.check_left_recursion()?;
+        Ok(g)
+    }
+
+    fn resolve(&self, node:&mut Node) -> Result<(), (String, usize)> {
+        match node {
+            Node::Seq(v) | Node::Alt(v) => { for i in v { self.resolve(i)?; } },
+            Node::Repeat(_, _, x) => self.resolve(x)?,
+            Node::Except(a, b) => { self.resolve(a)?; self.resolve(b)?; },
+            Node::Ref(name, rest) => match self.index(name) {
+                Some(i) => *node = Node::Rule(i),
+                None    => return Err((format!("rule `{}` is not defined", name), *rest)),
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn nullable(&self, node:&Node, known:&[bool]) -> bool {
+        match node {
+            Node::Lit(l, _)        => l.is_empty(),
+            Node::Seq(v)           => v.iter().all(|x| self.nullable(x, known)),
+            Node::Alt(v)           => v.iter().any(|x| self.nullable(x, known)),
+            Node::Repeat(min,_,x)  => *min == 0 || self.nullable(x, known),
+            Node::Except(a, _)     => self.nullable(a, known),
+            Node::Rule(i)          => known[*i],
+            _                      => false,
+        }
+    }
+
+    /// rules which can be called at the same position of input
+    fn leftmost(&self, node:&Node, known:&[bool], r:&mut Vec<usize>) {
+        match node {
+            Node::Seq(v) => for x in v {
+                self.leftmost(x, known, r);
+                if !self.nullable(x, known) { break; }
+            },
+            Node::Alt(v) => for x in v { self.leftmost(x, known, r); },
+            Node::Repeat(_,_,x) => self.leftmost(x, known, r),
+            Node::Except(a, b) => { self.leftmost(a, known, r); self.leftmost(b, known, r); },
+            Node::Rule(i) => r.push(*i),
+            _ => {},
+        }
+    }
+
+    fn check_left_recursion(&self) -> Result<(), ParseError> {
+        let mut known = vec![false; self.rules.len()];
+        loop {
+            let next: Vec<bool> = self.rules.iter().map(|x| self.nullable(x, &known)).collect();
+            if next == known { break; }
+            known = next;
+        }
+        let edges: Vec<Vec<usize>> = self.rules.iter().map(|x| { let mut r = Vec::new(); self.leftmost(x, &known, &mut r); r }).collect();
+        for start in 0..self.rules.len() {
+            let mut seen = vec![false; self.rules.len()];
+            let mut stack = edges[start].clone();
+            while let Some(i) = stack.pop() {
+                if i == start {
+                    let msg = format!("rule `{}` is left recursive", self.names[start]);
+                    return Err(PErr::<u8>::new(&[]).user_msg_push(Msg::String(msg)).into());
+                }
+                if !seen[i] { seen[i] = true; stack.extend(&edges[i]); }
+            }
+        }
+        Ok(())
+    }
+
+    fn eval_rule<'a>(&'a self, idx:usize, input:&'a [u8]) -> ParseResult<'a,u8,()> {
+        self.eval(&self.rules[idx], input).map_err(|e| {
+            e.user_msg_push(Msg::String(format!("rule `{}` failed", self.names[idx]))).fmt_str()
+        })
+    }
+
+    fn eval<'a>(&'a self, node:&'a Node, input:&'a [u8]) -> ParseResult<'a,u8,()> {
+        match node {
+            Node::Lit(l, false) => {
+                let (input, _) = starts_with(l.as_slice()).parse(input)?;
+                Ok((input, ()))
+            },
+            Node::Lit(l, true) => {
+                if input.len() < l.len() { return Err(PErr::new(input).set_eod()); }
+                if !input[..l.len()].eq_ignore_ascii_case(l) { return Err(PErr::new(input)); }
+                Ok((&input[l.len()..], ()))
+            },
+            Node::Class(c) => {
+                if input.is_empty() { return Err(PErr::new(input).set_eod()); }
+                c.parse(&input[..1]).map_err(|_| PErr::new(input))?;
+                Ok((&input[1..], ()))
+            },
+            Node::Seq(v) => {
+                let mut input = input;
+                for x in v { (input, _) = self.eval(x, input)?; }
+                Ok((input, ()))
+            },
+            Node::Alt(v) => {
+                let mut er = PErr::new(input);
+                for x in v {
+                    match self.eval(x, input) {
+                        Ok(r)  => return Ok(r),
+                        Err(e) => er = e,
+                    }
+                }
+                Err(er)
+            },
+            Node::Repeat(min, max, x) => {
+                if *max == 0 { return Ok((input, ())); }
+                // an element matching empty input stops the repetition, not loops forever
+                let element = move |i:&'a [u8]| match self.eval(x, i) {
+                    Ok((rest, _)) if rest.len() == i.len() => Err(PErr::new(i)),
+                    r => r,
+                };
+                let (rest, _) = more_range(element, (*min, *max)).parse(input)?;
+                Ok((rest, ()))
+            },
+            Node::Except(a, b) => {
+                if self.eval(b, input).is_ok() { return Err(PErr::new(input)); }
+                self.eval(a, input)
+            },
+            Node::Rule(i) => self.eval_rule(*i, input),
+            Node::Ref(..) => Err(PErr::new(input)),
+        }
+    }
+}
+
+/// just useful function
+fn load_err<'a>(e:PErr<'a,u8>, text:&'a [u8], msg:&'static str) -> ParseError {
+    e.user_msg_push(Msg::Str(msg)).fmt_str().into_owned(text)
+}
+
+/// parse every rule of the list up to the end of text
+fn rulelist<'a,P,E>(text:&'a [u8], rule:P, empty:E, ignore_case:bool) -> Result<Vec<(String, Node)>, ParseError>
+where
+    P: Parser<'a,u8,(&'a[u8], bool, Node)>,
+    E: Parser<'a,u8,()>,
+{
+    let mut rules: Vec<(String, Node)> = Vec::new();
+    let mut input = text;
+    loop {
+        let n = input.iter().take_while(|x| is_space(x)).count();
+        if n == input.len() { break; }
+        if let Ok((i, _)) = empty.parse(input) { input = i; continue; }
+        let (i, (name, incremental, node)) = rule.parse(input).map_err(|e| load_err(e, text, "grammar rule parse error"))?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        match rules.iter_mut().find(|x| x.0 == name || (ignore_case && x.0.eq_ignore_ascii_case(&name))) {
+            Some((_, old)) if incremental => {
+                let prev = std::mem::replace(old, Node::Seq(Vec::new()));
+                *old = match prev { Node::Alt(mut v) => { v.push(node); Node::Alt(v) }, x => Node::Alt(vec![x, node]) };
+            },
+            Some(_) => return Err(load_err(PErr::new(input), text, "rule is defined more than once")),
+            None    => rules.push((name, node)),
+        }
+        input = i;
+    }
+    Ok(rules)
+}
+
+
+// ABNF
+
+fn abnf_rulelist(text:&[u8]) -> Result<Vec<(String, Node)>, ParseError> {
+    let empty = fmap(pair(more_min(abnf_c_wsp, 0), abnf_c_nl), |_|());
+    rulelist(text, abnf_rule, empty, true)
+}
+
+fn is_wsp(c:&u8) -> bool { *c == b' ' || *c == b'\t' }
+
+fn abnf_c_nl<'a>(input:&'a [u8]) -> ParseResult<'a,u8,()> {
+    let comment = pair(any(b";"), seq(is_no_eol).option());
+    fmap(pair(comment.option(), alt((starts_with(b"\r\n"), starts_with(b"\n")))), |_|()).parse(input)
+}
+
+fn abnf_c_wsp<'a>(input:&'a [u8]) -> ParseResult<'a,u8,()> {
+    fmap(alt((seq(is_wsp), right(abnf_c_nl, seq(is_wsp)))), |_|()).parse(input)
+}
+
+fn abnf_rulename<'a>(input:&'a [u8]) -> ParseResult<'a,u8,&'a[u8]> {
+    recognize(pair(seq_exact(is_alpha, 1), seq(|c:&u8| is_alphanum(c) || *c == b'-').option())).parse(input)
+}
+
+fn abnf_rule<'a>(input:&'a [u8]) -> ParseResult<'a,u8,(&'a[u8], bool, Node)> {
+    let sp0 = more_min(abnf_c_wsp, 0);
+    let defined_as = alt((starts_with(b"=/"), starts_with(b"=")));
+    let end = alt((abnf_c_nl, fmap(data_end, |_|())));
+    fmap(tuple((abnf_rulename, sp0, defined_as.expected("`=` or `=/`"), sp0, abnf_alternation, sp0, end.expected("end of line"))),
+        |(name, _, d, _, node, _, _)| (name, d.len() == 2, node)).parse(input)
+}
+
+fn abnf_alternation<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let sp0 = more_min(abnf_c_wsp, 0);
+    let next = right(tuple((sp0, any(b"/"), sp0)), abnf_concatenation);
+    fmap(pair(abnf_concatenation, more_min(next, 0)), |(first, mut v)| {
+        if v.is_empty() { return first; }
+        v.insert(0, first);
+        Node::Alt(v)
+    }).parse(input)
+}
+
+fn abnf_concatenation<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let next = right(more(abnf_c_wsp), abnf_repetition);
+    fmap(pair(abnf_repetition, more_min(next, 0)), |(first, mut v)| {
+        if v.is_empty() { return first; }
+        v.insert(0, first);
+        Node::Seq(v)
+    }).parse(input)
+}
+
+fn dec_usize<'a>(input:&'a [u8]) -> ParseResult<'a,u8,usize> {
+    frmap(seq(is_dec_digit), |x| from_utf8(x).unwrap_or_default().parse::<usize>()).parse(input)
+}
+
+fn abnf_repeat<'a>(input:&'a [u8]) -> ParseResult<'a,u8,(usize, usize)> {
+    if let Ok((input, (min, _, max))) = tuple((option(dec_usize), any(b"*"), option(dec_usize))).parse(input) {
+        return Ok((input, (min.unwrap_or(0), max.unwrap_or(usize::MAX))));
+    }
+    fmap(dec_usize, |n| (n, n)).parse(input)
+}
+
+fn abnf_repetition<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    fmap(pair(option(abnf_repeat), abnf_element), |(r, node)| match r {
+        Some((min, max)) => Node::Repeat(min, max, Box::new(node)),
+        None             => node,
+    }).parse(input)
+}
+
+fn abnf_element<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let sp0 = more_min(abnf_c_wsp, 0);
+    let rulename = move |i:&'a [u8]| {
+        let (rest, name) = abnf_rulename(i)?;
+        Ok((rest, Node::Ref(String::from_utf8_lossy(name).into_owned(), i.len())))
+    };
+    let group = between(pair(any(b"("), sp0), abnf_alternation, pair(sp0, any(b")").expected(")")));
+    let opt = fmap(between(pair(any(b"["), sp0), abnf_alternation, pair(sp0, any(b"]").expected("]"))),
+        |x| Node::Repeat(0, 1, Box::new(x)));
+    let prose = frmap(between(any(b"<"), seq(|c:&u8| *c != b'>').option(), any(b">")),
+        |_| Err::<Node,&str>("prose-val is not supported"));
+    // num-val is the only element of `%` and a base, its errors are not replaced by errors of other elements
+    if input.len() > 1 && input[0] == b'%' && b"bdxBDX".contains(&input[1]) { return abnf_num_val(input); }
+    alt((rulename, group, opt, abnf_char_val, abnf_num_val, prose)).expected("rule element").parse(input)
+}
+
+fn abnf_char_val<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let (input, pre) = option(alt((starts_with(b"%s"), starts_with(b"%S"), starts_with(b"%i"), starts_with(b"%I")))).parse(input)?;
+    let (input, s) = between(any(b"\""), seq(|c:&u8| *c != b'"').option(), any(b"\"")).parse(input)?;
+    let sensitive = pre.is_some_and(|x| x.eq_ignore_ascii_case(b"%s"));
+    Ok((input, Node::Lit(s.unwrap_or_default().to_vec(), !sensitive)))
+}
+
+/// number of `base` up to 0xFF, `msg` is the error of a greater one
+fn abnf_num<'a>(base:u32, msg:&'static str) -> impl Parser<'a,u8,u8> {
+    move |input:&'a [u8]| {
+        let (rest, x) = seq(move |c:&u8| (*c as char).is_digit(base)).parse(input)?;
+        let n = u8::from_str_radix(from_utf8(x).unwrap_or_default(), base)
+            .map_err(|_| PErr::msg(input, msg))?;
+        Ok((rest, n))
+    }
+}
+
+fn abnf_num_val<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let (input, b) = right(any(b"%"), any(b"bdxBDX")).parse(input)?;
+    let base = match to_lowercase(b[0]) { b'b' => 2, b'd' => 10, _ => 16 };
+    let num = abnf_num(base, "num-val above 0xFF is not supported");
+    let (input, first) = num.parse(input)?;
+    if let Some(input) = input.strip_prefix(b"-") {
+        let (input, last) = num.parse(input)?;
+        let mut c: ClassOfSymbols<u8> = Default::default();
+        c.range_enable_push(&[(first, last)]);
+        return Ok((input, Node::Class(c)));
+    }
+    let (input, rest) = more_min(right(any(b"."), num), 0).parse(input)?;
+    let mut v = vec![first];
+    v.extend(rest);
+    Ok((input, Node::Lit(v, false)))
+}
+
+
+// EBNF
+
+fn ebnf_rulelist(text:&[u8]) -> Result<Vec<(String, Node)>, ParseError> {
+    let empty = fmap(more(ebnf_comment), |_|());
+    rulelist(text, ebnf_rule, empty, false)
+}
+
+fn ebnf_comment<'a>(input:&'a [u8]) -> ParseResult<'a,u8,()> {
+    let comment = right(starts_with(b"/*"), find(starts_with(b"*/")));
+    fmap(alt((seq(is_space), comment)), |_|()).parse(input)
+}
+
+fn ebnf_ws<'a>(input:&'a [u8]) -> ParseResult<'a,u8,()> {
+    fmap(more_min(ebnf_comment, 0), |_|()).parse(input)
+}
+
+fn ebnf_name<'a>(input:&'a [u8]) -> ParseResult<'a,u8,&'a[u8]> {
+    let first = seq_exact(|c:&u8| is_alpha(c) || *c == b'_', 1);
+    recognize(pair(first, seq(|c:&u8| is_alphanum(c) || b"_-.".contains(c)).option())).parse(input)
+}
+
+fn ebnf_rule<'a>(input:&'a [u8]) -> ParseResult<'a,u8,(&'a[u8], bool, Node)> {
+    fmap(tuple((ebnf_name, ebnf_ws, starts_with(b"::=").expected("::="), ebnf_ws, ebnf_choice)),
+        |(name, _, _, _, node)| (name, false, node)).parse(input)
+}
+
+fn ebnf_choice<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let next = right(tuple((ebnf_ws, any(b"|"), ebnf_ws)), ebnf_seq);
+    fmap(pair(ebnf_seq, more_min(next, 0)), |(first, mut v)| {
+        if v.is_empty() { return first; }
+        v.insert(0, first);
+        Node::Alt(v)
+    }).parse(input)
+}
+
+fn ebnf_seq<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let head = tuple((ebnf_name, ebnf_ws, starts_with(b"::=")));
+    let item = right(ebnf_ws, right(not(head), ebnf_except));
+    fmap(more(item), |mut v| if v.len() == 1 { v.pop().unwrap() } else { Node::Seq(v) }).parse(input)
+}
+
+fn ebnf_except<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let except = right(tuple((ebnf_ws, any(b"-"), ebnf_ws)), ebnf_postfix);
+    fmap(pair(ebnf_postfix, option(except)), |(a, b)| match b {
+        Some(b) => Node::Except(Box::new(a), Box::new(b)),
+        None    => a,
+    }).parse(input)
+}
+
+fn ebnf_postfix<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    fmap(pair(ebnf_primary, option(any(b"?*+"))), |(node, op)| match op {
+        Some(b"?") => Node::Repeat(0, 1, Box::new(node)),
+        Some(b"*") => Node::Repeat(0, usize::MAX, Box::new(node)),
+        Some(_)    => Node::Repeat(1, usize::MAX, Box::new(node)),
+        None       => node,
+    }).parse(input)
+}
+
+fn ebnf_char<'a>(input:&'a [u8]) -> ParseResult<'a,u8,u8> {
+    right(starts_with(b"#x"), abnf_num(16, "code point above #xFF is not supported")).parse(input)
+}
+
+fn ebnf_primary<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let name = move |i:&'a [u8]| {
+        let (rest, name) = ebnf_name(i)?;
+        Ok((rest, Node::Ref(String::from_utf8_lossy(name).into_owned(), i.len())))
+    };
+    let group = between(pair(any(b"("), ebnf_ws), ebnf_choice, pair(ebnf_ws, any(b")").expected(")")));
+    let lit = |q:&'static [u8]| fmap(between(any(q), seq(move |c:&u8| *c != q[0]).option(), any(q)),
+        |s| Node::Lit(s.unwrap_or_default().to_vec(), false));
+    let char_ref = fmap(ebnf_char, |c| Node::Lit(vec![c], false));
+    alt((name, group, lit(b"'"), lit(b"\""), ebnf_class, char_ref)).expected("rule element").parse(input)
+}
+
+fn ebnf_class<'a>(input:&'a [u8]) -> ParseResult<'a,u8,Node> {
+    let one = alt((ebnf_char, fmap(seq_exact(|c:&u8| *c != b']', 1), |x| x[0])));
+    let item = pair(one, option(right(any(b"-"), one)));
+    let (input, (neg, items)) = between(any(b"["), pair(option(any(b"^")), more(item)), any(b"]").expected("]")).parse(input)?;
+    let mut c: ClassOfSymbols<u8> = Default::default();
+    for (a, b) in items {
+        let r = (a, b.unwrap_or(a));
+        if neg.is_some() { c.range_disable_push(&[r]); } else { c.range_enable_push(&[r]); }
+    }
+    c.default_enable_one(neg.is_some());
+    Ok((input, Node::Class(c)))
+}
@@ -0,0 +1,109 @@
+//! Framed streams, feature `tokio`.
+//! `FrameDecoder` is a `tokio_util::codec::Decoder` over a parser: bytes are buffered,
+//! the parser is called on the buffer, an error with `is_eod()` waits for more data,
+//! other errors end the stream. Frames are owned: the buffer is reused after each frame.
+//!
+//! ```text
+//! fn frame(input:&[u8]) -> ParseResult<'_,u8,Vec<u8>> { ... }
+//! let mut frames = frame_reader(socket, frame);
+//! while let Some(f) = frames.next().await { ... }
+//! ```
+
+use crate::parser_combinators::{*};
+use bytes::{Buf,BytesMut};
+use std::{fmt,error,io};
+use tokio::io::AsyncRead;
+use tokio_util::codec::{Decoder,FramedRead};
+
+/// default max size of buffered frame
+pub const MAX_FRAME_LEN: usize = 8*1024*1024;
+
+/// Error of framed stream
+#[derive(Debug)]
+pub enum FrameError {
+    Io(io::Error),
+    /// parser failed, offset is from the beginning of the frame
+    Parse(ParseError),
+    /// frame is not complete and the buffer is over the limit
+    TooLong(usize),
+    /// stream is closed in the middle of a frame
+    Incomplete(usize),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrameError::Io(e)         => write!(f, "io error: {}", e),
+            FrameError::Parse(e)      => write!(f, "frame parse error: {}", e),
+            FrameError::TooLong(n)    => write!(f, "frame is longer than {} bytes", n),
+            FrameError::Incomplete(n) => write!(f, "stream closed with {} bytes of incomplete frame", n),
+        }
+    }
+}
+
+impl error::Error for FrameError {}
+
+impl From<io::Error> for FrameError {
+    fn from(e: io::Error) -> Self { FrameError::Io(e) }
+}
+
+/// Decoder of frames by parser
+#[derive(Debug,Clone,Copy)]
+pub struct FrameDecoder<F> {
+    parser: F,
+    max_len: usize,
+}
+
+impl<F> FrameDecoder<F> {
+    pub fn new(parser: F) -> Self { FrameDecoder { parser, max_len: MAX_FRAME_LEN } }
+
+    /// max size of buffered frame
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl<F,O> Decoder for FrameDecoder<F>
+where
+    F: for<'b> Fn(&'b [u8]) -> ParseResult<'b,u8,O>,
+{
+    type Item = O;
+    type Error = FrameError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<O>, FrameError> {
+        if src.is_empty() { return Ok(None); }
+        match (self.parser)(src) {
+            Ok((rest, r)) => {
+                let n = src.len()-rest.len();
+                if n == 0 {
+                    let e = PErr::msg(&src[..], "frame parser consumed nothing");
+                    return Err(FrameError::Parse(e.into_owned(src)));
+                }
+                src.advance(n);
+                Ok(Some(r))
+            },
+            Err(e) if e.is_eod() => {
+                if src.len() > self.max_len { return Err(FrameError::TooLong(self.max_len)); }
+                Ok(None)
+            },
+            Err(e) => Err(FrameError::Parse(e.into_owned(src))),
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<O>, FrameError> {
+        match self.decode(src)? {
+            Some(r) => Ok(Some(r)),
+            None if src.is_empty() => Ok(None),
+            None => Err(FrameError::Incomplete(src.len())),
+        }
+    }
+}
+
+/// stream of frames read from `AsyncRead`
+pub fn frame_reader<R:AsyncRead,F,O>(read: R, parser: F) -> FramedRead<R,FrameDecoder<F>>
+where
+    F: for<'b> Fn(&'b [u8]) -> ParseResult<'b,u8,O>,
+{
+    FramedRead::new(read, FrameDecoder::new(parser))
+}
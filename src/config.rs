@@ -0,0 +1,415 @@
+//! Config files: INI and a TOML subset.
+//! Sections `[a.b]`, dotted keys, basic and literal strings, integers, floats, booleans,
+//! arrays and comments. Not supported: multi-line strings, inline tables, arrays of tables
+//! and dates. Every value has a span, so errors of the caller can point at the line.
+//! Errors of the parser are `ParseError` with the offset, see `report::Report`.
+//!
+//! ```text
+//! let doc = config::parse(text)?;
+//! let port = doc.get_path("server.port").and_then(|x| x.value.as_i64());
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::error::ParseError;
+use std::borrow::Cow;
+
+/// place of a value in the source, `line` from 1
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+}
+
+/// value of a key
+#[derive(Debug,Clone,PartialEq)]
+pub enum Value<'a> {
+    String(Cow<'a,str>),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Item<'a>>),
+    Table(Table<'a>),
+}
+
+impl<'a> Value<'a> {
+    pub fn as_str(&self) -> Option<&str> { if let Value::String(x) = self { Some(x) } else { None } }
+    pub fn as_i64(&self) -> Option<i64> { if let Value::Integer(x) = self { Some(*x) } else { None } }
+    /// float or integer
+    pub fn as_f64(&self) -> Option<f64> {
+        match self { Value::Float(x) => Some(*x), Value::Integer(x) => Some(*x as f64), _ => None }
+    }
+    pub fn as_bool(&self) -> Option<bool> { if let Value::Boolean(x) = self { Some(*x) } else { None } }
+    pub fn as_array(&self) -> Option<&[Item<'a>]> { if let Value::Array(x) = self { Some(x) } else { None } }
+    pub fn as_table(&self) -> Option<&Table<'a>> { if let Value::Table(x) = self { Some(x) } else { None } }
+}
+
+/// value with its span, span of a table is its header or the first key defining it
+#[derive(Debug,Clone,PartialEq)]
+pub struct Item<'a> {
+    pub value: Value<'a>,
+    pub span: Span,
+}
+
+/// keys in order of the source
+#[derive(Debug,Clone,Default,PartialEq)]
+pub struct Table<'a>(pub Vec<(Cow<'a,str>, Item<'a>)>);
+
+impl<'a> Table<'a> {
+    pub fn get(&self, key: &str) -> Option<&Item<'a>> { self.0.iter().find(|x| x.0 == key).map(|x| &x.1) }
+
+    /// value by dotted path of bare keys, e.g. `server.port`
+    pub fn get_path(&self, path: &str) -> Option<&Item<'a>> {
+        let mut keys = path.split('.');
+        let mut item = self.get(keys.next()?)?;
+        for k in keys { item = item.value.as_table()?.get(k)?; }
+        Some(item)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Item<'a>)> { self.0.iter().map(|x| (x.0.as_ref(), &x.1)) }
+    pub fn len(&self) -> usize { self.0.len() }
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+}
+
+/// syntax options
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Config {
+    bare_values: bool,
+    semicolon_comments: bool,
+    colon_assign: bool,
+    spaced_keys: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self { Config::toml() }
+}
+
+impl Config {
+    /// TOML subset: typed values only, `#` comments, `=`
+    pub fn toml() -> Self { Config { bare_values: false, semicolon_comments: false, colon_assign: false, spaced_keys: false } }
+
+    /// INI: also bare string values, `;` comments, `:` and spaces in keys
+    pub fn ini() -> Self { Config { bare_values: true, semicolon_comments: true, colon_assign: true, spaced_keys: true } }
+
+    /// a value that is not typed is a string up to the end of line or a comment after a space
+    pub fn bare_values(mut self, x: bool) -> Self {
+        self.bare_values = x;
+        self
+    }
+
+    pub fn semicolon_comments(mut self, x: bool) -> Self {
+        self.semicolon_comments = x;
+        self
+    }
+
+    /// `key: value`
+    pub fn colon_assign(mut self, x: bool) -> Self {
+        self.colon_assign = x;
+        self
+    }
+
+    /// spaces inside bare keys and section names, e.g. `[My Section]` or `log level = debug`,
+    /// spaces at the ends are not in the key
+    pub fn spaced_keys(mut self, x: bool) -> Self {
+        self.spaced_keys = x;
+        self
+    }
+
+    /// parse a document
+    pub fn parse(self, input: &[u8]) -> Result<Table<'_>, ParseError> {
+        let p = Doc { cfg: self, src: input, lines: input.iter().enumerate().filter(|x| *x.1 == b'\n').map(|x| x.0).collect() };
+        p.document().map_err(|e| e.into_owned(input))
+    }
+}
+
+/// parse a document of the TOML subset
+pub fn parse(input: &[u8]) -> Result<Table<'_>, ParseError> { Config::toml().parse(input) }
+
+fn is_bare_key(i: &u8) -> bool { i.is_ascii_alphanumeric() || *i == b'_' || *i == b'-' }
+
+/// spaces and tabs
+fn ws(input: &[u8]) -> &[u8] { &input[input.iter().take_while(|x| matches!(x, b' ' | b'\t')).count()..] }
+
+struct Doc<'a> {
+    cfg: Config,
+    src: &'a [u8],
+    /// offsets of line breaks
+    lines: Vec<usize>,
+}
+
+impl<'a> Doc<'a> {
+    fn offset(&self, part: &[u8]) -> usize { self.src.len() - part.len() }
+
+    /// span from `start` to `end`, both suffixes of the source
+    fn span(&self, start: &[u8], end: &[u8]) -> Span {
+        let start = self.offset(start);
+        Span { start, end: self.offset(end), line: self.lines.partition_point(|x| *x < start) + 1 }
+    }
+
+    fn is_comment(&self, i: &[u8]) -> bool {
+        matches!(i.first(), Some(b'#')) || self.cfg.semicolon_comments && matches!(i.first(), Some(b';'))
+    }
+
+    /// rest of the line: spaces, comment and line break or end of input
+    fn end_of_line(&self, input: &'a [u8]) -> Result<&'a [u8], PErr<'a,u8>> {
+        let mut i = ws(input);
+        if self.is_comment(i) {
+            let n = i.iter().position(|x| *x == b'\n').unwrap_or(i.len());
+            i = &i[n..];
+        }
+        match i {
+            []                    => Ok(i),
+            [b'\n', rest @ ..]    => Ok(rest),
+            [b'\r', b'\n', rest @ ..] => Ok(rest),
+            _ => Err(PErr::new(i).expected_push(Msg::Str("end of line")).fmt_str()),
+        }
+    }
+
+    fn document(&self) -> Result<Table<'a>, PErr<'a,u8>> {
+        let mut root = Table::default();
+        let mut section: Vec<Cow<'a,str>> = Vec::new();
+        let mut headers: Vec<Vec<Cow<'a,str>>> = Vec::new();
+        let mut input = self.src;
+        loop {
+            let i = ws(input);
+            if i.is_empty() { return Ok(root); }
+            if i.starts_with(b"[[") { return Err(PErr::msg(i, "arrays of tables are not supported")); }
+            if let Some(h) = i.strip_prefix(b"[") {
+                let (h, path) = self.key_path(ws(h))?;
+                let (h, _) = expected(starts_with(b"]"), "`]`").parse(ws(h))?;
+                if headers.contains(&path) { return Err(PErr::msg(i, "duplicate table")); }
+                let span = self.span(i, h);
+                insert_table(&mut root, &path, span).map_err(|m| PErr::msg(i, m))?;
+                headers.push(path.clone());
+                section = path;
+                input = self.end_of_line(h)?;
+            } else if self.is_comment(i) || matches!(i.first(), Some(b'\r' | b'\n')) {
+                input = self.end_of_line(i)?;
+            } else {
+                let (next, path) = self.key_path(i)?;
+                let assign: &[u8] = if self.cfg.colon_assign { b"=:" } else { b"=" };
+                let (next, _) = expected(any(assign), "`=`").parse(ws(next))?;
+                let (next, item) = self.item(ws(next))?;
+                let full: Vec<_> = section.iter().cloned().chain(path).collect();
+                insert_value(&mut root, &full, item).map_err(|m| PErr::msg(i, m))?;
+                input = self.end_of_line(next)?;
+            }
+        }
+    }
+
+    /// key `a.b."c d"`
+    fn key_path(&self, input: &'a [u8]) -> ParseResult<'a,u8,Vec<Cow<'a,str>>> {
+        let mut r = Vec::new();
+        let mut i = input;
+        loop {
+            let (next, k) = match i.first() {
+                Some(b'"')  => self.basic_string(i)?,
+                Some(b'\'') => self.literal_string(i)?,
+                _ => {
+                    let n = if self.cfg.spaced_keys {
+                        let n = i.iter().take_while(|x| is_bare_key(x) || matches!(x, b' ' | b'\t')).count();
+                        i[..n].iter().rposition(is_bare_key).map_or(0, |k| k+1)
+                    } else {
+                        i.iter().take_while(|x| is_bare_key(x)).count()
+                    };
+                    if n == 0 { return Err(PErr::new(i).expected_push(Msg::Str("key")).fmt_str()); }
+                    let (next, k) = split_at_revers(i, n);
+                    (next, Cow::Borrowed(std::str::from_utf8(k).unwrap_or_default()))
+                },
+            };
+            r.push(k);
+            match ws(next).strip_prefix(b".") {
+                Some(x) => i = ws(x),
+                None    => return Ok((next, r)),
+            }
+        }
+    }
+
+    /// value with span
+    fn item(&self, input: &'a [u8]) -> ParseResult<'a,u8,Item<'a>> {
+        if self.cfg.bare_values && input.first() != Some(&b'[') {
+            let n = input.iter().position(|x| *x == b'\n').unwrap_or(input.len());
+            let line = &input[..n];
+            // comment after a space, trailing spaces are not in the value
+            let mut end = line.len();
+            for (k, w) in line.windows(2).enumerate() {
+                if matches!(w[0], b' ' | b'\t') && self.is_comment(&w[1..]) { end = k; break; }
+            }
+            while end > 0 && matches!(line[end-1], b' ' | b'\t' | b'\r') { end -= 1; }
+            let (rest, raw) = split_at_revers(input, end);
+            return match self.value(raw) {
+                Ok(([], v)) => Ok((rest, Item { value: v, span: self.span(input, rest) })),
+                _ => {
+                    let s = std::str::from_utf8(raw).map_err(|_| PErr::msg(input, "invalid utf-8"))?;
+                    Ok((rest, Item { value: Value::String(Cow::Borrowed(s)), span: self.span(input, rest) }))
+                },
+            };
+        }
+        let (rest, v) = self.value(input)?;
+        Ok((rest, Item { value: v, span: self.span(input, rest) }))
+    }
+
+    fn value(&self, input: &'a [u8]) -> ParseResult<'a,u8,Value<'a>> {
+        match input.first() {
+            None        => Err(PErr::new(input).set_eod().expected_push(Msg::Str("value"))),
+            Some(b'"')  => fmap(|i| self.basic_string(i), Value::String).parse(input),
+            Some(b'\'') => fmap(|i| self.literal_string(i), Value::String).parse(input),
+            Some(b'[')  => self.array(input),
+            Some(b'{')  => Err(PErr::msg(input, "inline tables are not supported")),
+            Some(_)     => {
+                let n = input.iter().take_while(|x| x.is_ascii_alphanumeric() || b"_+-.".contains(x)).count();
+                let (rest, token) = split_at_revers(input, n);
+                let v = match token {
+                    b"true"  => Value::Boolean(true),
+                    b"false" => Value::Boolean(false),
+                    _        => number(token).ok_or_else(|| PErr::new(input).expected_push(Msg::Str("value")).fmt_str())?,
+                };
+                Ok((rest, v))
+            },
+        }
+    }
+
+    /// spaces, line breaks and comments inside an array
+    fn array_ws(&self, input: &'a [u8]) -> &'a [u8] {
+        let mut i = input;
+        loop {
+            i = ws(i);
+            match i.first() {
+                Some(b'\r' | b'\n') => i = &i[1..],
+                _ if self.is_comment(i) => i = &i[i.iter().position(|x| *x == b'\n').unwrap_or(i.len())..],
+                _ => return i,
+            }
+        }
+    }
+
+    /// `[` value (`,` value)* `,`? `]`
+    fn array(&self, input: &'a [u8]) -> ParseResult<'a,u8,Value<'a>> {
+        let mut r = Vec::new();
+        let mut i = self.array_ws(&input[1..]);
+        loop {
+            if let Some(x) = i.strip_prefix(b"]") { return Ok((x, Value::Array(r))); }
+            let (next, v) = self.value(i)?;
+            r.push(Item { value: v, span: self.span(i, next) });
+            i = self.array_ws(next);
+            match i.first() {
+                Some(b',') => i = self.array_ws(&i[1..]),
+                Some(b']') => {},
+                None       => return Err(PErr::new(i).set_eod().expected_push(Msg::Str("`]`"))),
+                Some(_)    => return Err(PErr::new(i).expected_push(Msg::Str("`,` or `]`")).fmt_str()),
+            }
+        }
+    }
+
+    /// `'...'`, no escapes
+    fn literal_string(&self, input: &'a [u8]) -> ParseResult<'a,u8,Cow<'a,str>> {
+        let body = &input[1..];
+        let n = body.iter().position(|x| *x == b'\'' || *x == b'\n')
+            .filter(|n| body[*n] == b'\'')
+            .ok_or_else(|| PErr::msg(input, "unterminated string"))?;
+        let s = std::str::from_utf8(&body[..n]).map_err(|_| PErr::msg(input, "invalid utf-8"))?;
+        Ok((&body[n+1..], Cow::Borrowed(s)))
+    }
+
+    /// `"..."` with escapes, borrows the input if there are none
+    fn basic_string(&self, input: &'a [u8]) -> ParseResult<'a,u8,Cow<'a,str>> {
+        let body = &input[1..];
+        let mut i = 0;
+        let mut escaped = false;
+        loop {
+            match body.get(i) {
+                None | Some(b'\n') => return Err(PErr::msg(input, "unterminated string")),
+                Some(b'"')     => break,
+                Some(b'\\')    => { escaped = true; i += 2; },
+                Some(_)        => i += 1,
+            }
+        }
+        let rest = &body[i+1..];
+        let s = std::str::from_utf8(&body[..i]).map_err(|_| PErr::msg(input, "invalid utf-8"))?;
+        if !escaped { return Ok((rest, Cow::Borrowed(s))); }
+        let mut r = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' { r.push(c); continue; }
+            let c = match chars.next() {
+                Some('b')  => '\x08',
+                Some('t')  => '\t',
+                Some('n')  => '\n',
+                Some('f')  => '\x0c',
+                Some('r')  => '\r',
+                Some('"')  => '"',
+                Some('\\') => '\\',
+                Some(u @ ('u' | 'U')) => {
+                    let n = if u == 'u' { 4 } else { 8 };
+                    let h: String = chars.by_ref().take(n).collect();
+                    u32::from_str_radix(&h, 16).ok().filter(|_| h.len() == n && h.chars().all(|x| x.is_ascii_hexdigit()))
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| PErr::msg(input, "invalid unicode escape"))?
+                },
+                _ => return Err(PErr::msg(input, "invalid escape in string")),
+            };
+            r.push(c);
+        }
+        Ok((rest, Cow::Owned(r)))
+    }
+}
+
+/// TOML integer or float, `_` between digits
+fn number(token: &[u8]) -> Option<Value<'static>> {
+    let s = std::str::from_utf8(token).ok()?;
+    let digits = |x: &str, radix: u32| {
+        !x.is_empty() && !x.starts_with('_') && !x.ends_with('_') && !x.contains("__")
+            && x.chars().all(|c| c == '_' || c.is_digit(radix))
+    };
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(x) = s.strip_prefix(prefix) {
+            return digits(x, radix).then(|| i64::from_str_radix(&x.replace('_', ""), radix).ok()).flatten().map(Value::Integer);
+        }
+    }
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    match unsigned {
+        "inf" => return Some(Value::Float(if s.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY })),
+        "nan" => return Some(Value::Float(f64::NAN)),
+        _ => {},
+    }
+    let (mantissa, exp) = match unsigned.find(['e', 'E']) {
+        Some(n) => (&unsigned[..n], Some(&unsigned[n+1..])),
+        None    => (unsigned, None),
+    };
+    let (int, frac) = match mantissa.split_once('.') {
+        Some((a, b)) => (a, Some(b)),
+        None         => (mantissa, None),
+    };
+    if !digits(int, 10) || int.len() > 1 && int.starts_with('0') { return None; }
+    if frac.is_some_and(|x| !digits(x, 10)) { return None; }
+    if exp.is_some_and(|x| !digits(x.strip_prefix(['+', '-']).unwrap_or(x), 10)) { return None; }
+    let clean = s.replace('_', "");
+    if frac.is_none() && exp.is_none() { clean.parse().ok().map(Value::Integer) } else { clean.parse().ok().map(Value::Float) }
+}
+
+/// table at the path, created with intermediate tables if missing
+fn table_at<'t,'a>(root: &'t mut Table<'a>, path: &[Cow<'a,str>], span: Span) -> Result<&'t mut Table<'a>, &'static str> {
+    let mut t = root;
+    for k in path {
+        let n = match t.0.iter().position(|x| x.0 == *k) {
+            Some(n) => n,
+            None    => { t.0.push((k.clone(), Item { value: Value::Table(Table::default()), span })); t.0.len()-1 },
+        };
+        t = match &mut t.0[n].1.value {
+            Value::Table(x) => x,
+            _ => return Err("key is not a table"),
+        };
+    }
+    Ok(t)
+}
+
+fn insert_table<'a>(root: &mut Table<'a>, path: &[Cow<'a,str>], span: Span) -> Result<(), &'static str> {
+    table_at(root, path, span).map(|_| ())
+}
+
+fn insert_value<'a>(root: &mut Table<'a>, path: &[Cow<'a,str>], item: Item<'a>) -> Result<(), &'static str> {
+    let Some((key, parent)) = path.split_last() else { return Err("empty key") };
+    let t = table_at(root, parent, item.span)?;
+    if t.get(key).is_some() { return Err("duplicate key"); }
+    t.0.push((key.clone(), item));
+    Ok(())
+}
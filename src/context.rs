@@ -0,0 +1,224 @@
+//! Stateful parsing.
+//! `CtxParser` is a parser flavour that threads a user defined state `S` through the parse
+//! by `&mut`, so grammars can depend on earlier results (indentation, header flags, ...)
+//! without `Cell`s captured in closures.
+
+use crate::parser_combinators::{*};
+
+/// Parser with user context `S`
+pub trait CtxParser<'a,I:'a,S,O>: Copy {
+    fn parse_ctx(&self, input:&'a [I], st:&mut S) -> ParseResult<'a,I,O>;
+}
+
+impl<'a,I:'a,S,F,O> CtxParser<'a,I,S,O> for F
+where
+    F: Fn(&'a[I], &mut S) -> ParseResult<'a,I,O>+Copy,
+{
+    fn parse_ctx(&self, input:&'a [I], st:&mut S) -> ParseResult<'a,I,O> { self(input, st) }
+}
+
+/// lift a plain parser into a context parser, the state is not touched
+pub fn ctx_lift<'a,I:'a,S,P,R>(p:P) -> impl CtxParser<'a,I,S,R>
+where
+    P: Parser<'a,I,R>,
+{
+    move |input:&'a[I], _:&mut S| { p.parse(input) }
+}
+
+/// read the state, consumes nothing
+pub fn ctx_get<'a,I:'a,S,F,R>(f:F) -> impl CtxParser<'a,I,S,R>
+where
+    F: Fn(&S) -> R + Copy,
+{
+    move |input:&'a[I], st:&mut S| { Ok((input, f(st))) }
+}
+
+/// change the state, consumes nothing
+pub fn ctx_set<'a,I:'a,S,F>(f:F) -> impl CtxParser<'a,I,S,()>
+where
+    F: Fn(&mut S) + Copy,
+{
+    move |input:&'a[I], st:&mut S| { f(st); Ok((input, ())) }
+}
+
+/// guard, returns Err if the state does not satisfy the predicate, consumes nothing
+pub fn ctx_check<'a,I:'a,S,F>(f:F) -> impl CtxParser<'a,I,S,()>
+where
+    F: Fn(&S) -> bool + Copy,
+{
+    move |input:&'a[I], st:&mut S| {
+        if f(st) { Ok((input, ())) } else { Err(PErr::new(input)) }
+    }
+}
+
+/// run parser and update the state by its result
+pub fn ctx_with<'a,I:'a,S,P,F,R>(p:P, f:F) -> impl CtxParser<'a,I,S,R>
+where
+    P: CtxParser<'a,I,S,R>,
+    F: Fn(&mut S, &R) + Copy,
+{
+    move |input:&'a[I], st:&mut S| {
+        let (input, r) = p.parse_ctx(input, st)?;
+        f(st, &r);
+        Ok((input, r))
+    }
+}
+
+/// build a plain parser from the current state and run it,
+/// e.g. `ctx_parser(|st:&Header| take(st.len))`
+pub fn ctx_parser<'a,I:'a,S,F,P,R>(f:F) -> impl CtxParser<'a,I,S,R>
+where
+    F: Fn(&S) -> P + Copy,
+    P: Parser<'a,I,R>,
+{
+    move |input:&'a[I], st:&mut S| { f(st).parse(input) }
+}
+
+/// scope: change the state by `f`, run parser and restore the state as it was before
+pub fn ctx_scope<'a,I:'a,S:Clone,F,P,R>(f:F, p:P) -> impl CtxParser<'a,I,S,R>
+where
+    F: Fn(&mut S) + Copy,
+    P: CtxParser<'a,I,S,R>,
+{
+    move |input:&'a[I], st:&mut S| {
+        let old = st.clone();
+        f(st);
+        let r = p.parse_ctx(input, st);
+        *st = old;
+        r
+    }
+}
+
+/// combinator fmap
+pub fn ctx_fmap<'a,I:'a,S,P,F,R1,R2>(p:P, map_fn:F) -> impl CtxParser<'a,I,S,R2>
+where
+    P: CtxParser<'a,I,S,R1>,
+    F: Fn(R1) -> R2 + Copy,
+{
+    move |input:&'a[I], st:&mut S| {
+        p.parse_ctx(input, st).map(|(input, r)| (input, map_fn(r)))
+    }
+}
+
+/// combinator pair
+pub fn ctx_pair<'a,I:'a,S,P1,P2,R1,R2>(p1:P1, p2:P2) -> impl CtxParser<'a,I,S,(R1,R2)>
+where
+    P1: CtxParser<'a,I,S,R1>,
+    P2: CtxParser<'a,I,S,R2>,
+{
+    move |input:&'a[I], st:&mut S| {
+        let (input, r1) = p1.parse_ctx(input, st)?;
+        let (input, r2) = p2.parse_ctx(input, st)?;
+        Ok((input, (r1, r2)))
+    }
+}
+
+/// combinator left
+pub fn ctx_left<'a,I:'a,S,P1,P2,R1,R2>(p1:P1, p2:P2) -> impl CtxParser<'a,I,S,R1>
+where
+    P1: CtxParser<'a,I,S,R1>,
+    P2: CtxParser<'a,I,S,R2>,
+{
+    ctx_fmap(ctx_pair(p1, p2), |(l,_)| l)
+}
+
+/// combinator right
+pub fn ctx_right<'a,I:'a,S,P1,P2,R1,R2>(p1:P1, p2:P2) -> impl CtxParser<'a,I,S,R2>
+where
+    P1: CtxParser<'a,I,S,R1>,
+    P2: CtxParser<'a,I,S,R2>,
+{
+    ctx_fmap(ctx_pair(p1, p2), |(_,r)| r)
+}
+
+/// combinator alt of two parsers, the state is restored before the second branch
+pub fn ctx_alt<'a,I:'a,S:Clone,P1,P2,R>(p1:P1, p2:P2) -> impl CtxParser<'a,I,S,R>
+where
+    P1: CtxParser<'a,I,S,R>,
+    P2: CtxParser<'a,I,S,R>,
+{
+    move |input:&'a[I], st:&mut S| {
+        let old = st.clone();
+        match p1.parse_ctx(input, st) {
+            Ok(r) => Ok(r),
+            Err(_) => { *st = old; p2.parse_ctx(input, st) },
+        }
+    }
+}
+
+/// combinator option - allways return Ok, the state is restored if parser fails
+pub fn ctx_option<'a,I:'a,S:Clone,P,R>(p:P) -> impl CtxParser<'a,I,S,Option<R>>
+where
+    P: CtxParser<'a,I,S,R>,
+{
+    move |input:&'a[I], st:&mut S| {
+        let old = st.clone();
+        match p.parse_ctx(input, st) {
+            Ok((input, r)) => Ok((input, Some(r))),
+            Err(_)         => { *st = old; Ok((input, None)) },
+        }
+    }
+}
+
+/// combinator `more no zero`, the state is restored to the last successful iteration
+pub fn ctx_more<'a,I:'a,S:Clone,P,R>(p:P) -> impl CtxParser<'a,I,S,Vec<R>>
+where
+    P: CtxParser<'a,I,S,R>,
+{
+    move |input:&'a[I], st:&mut S| {
+        let mut result = Vec::new();
+        let mut next_input1 = input;
+        loop {
+            let old = st.clone();
+            match p.parse_ctx(next_input1, st) {
+                Ok((next_input2, r)) => {
+                    result.push(r);
+                    next_input1 = next_input2;
+                },
+                Err(e) => {
+                    *st = old;
+                    if result.is_empty() { return Err(e); }
+                    break;
+                },
+        }}
+        Ok((next_input1, result))
+    }
+}
+
+/// whole input of the parse as the state, for formats with offsets into the message,
+/// e.g. DNS name compression: `ctx_at(offset, p)` parses at an offset of the source
+#[derive(Debug,Clone,Copy)]
+pub struct Source<'a,I>(&'a [I]);
+
+impl<'a,I> Source<'a,I> {
+    pub fn new(source:&'a [I]) -> Self { Source(source) }
+
+    /// whole input
+    pub fn get(&self) -> &'a [I] { self.0 }
+
+    /// offset of a part of the source, `None` if it is not in the source
+    pub fn offset(&self, part:&[I]) -> Option<usize> {
+        let size = std::mem::size_of::<I>().max(1);
+        let start = self.0.as_ptr() as usize;
+        let p = part.as_ptr() as usize;
+        if p < start || p > start + self.0.len()*size { return None; }
+        Some((p - start) / size)
+    }
+
+    /// the source from the offset
+    pub fn at(&self, offset:usize) -> Option<&'a [I]> { self.0.get(offset..) }
+}
+
+/// parse at the offset of the source, the input is not consumed
+pub fn ctx_at<'a,I:'a,P,R>(offset:usize, p:P) -> impl CtxParser<'a,I,Source<'a,I>,R>
+where
+    P: CtxParser<'a,I,Source<'a,I>,R>,
+{
+    move |input:&'a[I], st:&mut Source<'a,I>| {
+        let Some(part) = st.at(offset) else {
+            return Err(PErr::new(input).user_msg_push(Msg::Str("offset is out of the source")));
+        };
+        let (_, r) = p.parse_ctx(part, st)?;
+        Ok((input, r))
+    }
+}
@@ -0,0 +1,157 @@
+//! CSV, RFC 4180.
+//! Delimiter, quote and escape are configurable, quoted fields may have doubled quotes,
+//! delimiters and line breaks. Fields borrow the input if nothing is unescaped.
+//! Records end with CRLF or LF, the last line break is optional.
+//!
+//! ```text
+//! let mut rd = Csv::new().delimiter(b';').reader(input);
+//! let header = rd.header().map(|x| x.to_vec());
+//! for r in rd { let record = r?; ... }
+//! ```
+
+use crate::parser_combinators::{*};
+use std::borrow::Cow;
+
+/// record of fields
+pub type Record<'a> = Vec<Cow<'a,[u8]>>;
+
+/// CSV dialect
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Csv {
+    delimiter: u8,
+    quote: u8,
+    escape: Option<u8>,
+    header: bool,
+}
+
+impl Default for Csv {
+    fn default() -> Self { Csv { delimiter: b',', quote: b'"', escape: None, header: false } }
+}
+
+impl Csv {
+    /// RFC 4180: `,` and `"`, quote is escaped by doubling, no header
+    pub fn new() -> Self { Default::default() }
+
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// escape character in quoted fields, e.g. `\`, doubled quote is allowed anyway
+    pub fn escape(mut self, escape: Option<u8>) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// first record is header, see `Reader::header`
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// parser of one record with line break
+    pub fn record<'a>(self) -> impl Parser<'a,u8,Record<'a>> {
+        move |input: &'a [u8]| {
+            if input.is_empty() { return Err(PErr::new(input).set_eod()); }
+            let mut r = Vec::new();
+            let mut input = input;
+            loop {
+                let (next_input, f) = self.field(input)?;
+                r.push(f);
+                match next_input.first() {
+                    Some(x) if *x == self.delimiter => input = &next_input[1..],
+                    _ => {
+                        let (next_input, _) = alt((starts_with(b"\r\n"), starts_with(b"\n"), data_end)).parse(next_input)
+                            .map_err(|e| e.expected_push(Msg::Str("delimiter or line break")).fmt_str())?;
+                        return Ok((next_input, r));
+                    },
+                }
+            }
+        }
+    }
+
+    /// reader of records, the header is read at once, an error of it is the first item of the reader
+    pub fn reader(self, input: &[u8]) -> Reader<'_> {
+        let mut rd = Reader { csv: self, input, header: None, header_error: None, error: false };
+        if self.header && !input.is_empty() {
+            match self.record().parse(input) {
+                Ok((input, h)) => { rd.input = input; rd.header = Some(h); },
+                Err(e)         => rd.header_error = Some(e),
+            }
+        }
+        rd
+    }
+
+    fn field<'a>(&self, input: &'a [u8]) -> ParseResult<'a,u8,Cow<'a,[u8]>> {
+        if input.first() != Some(&self.quote) {
+            let n = input.iter().take_while(|x| **x != self.delimiter && **x != b'\n' && **x != b'\r').count();
+            let (rest, f) = split_at_revers(input, n);
+            return Ok((rest, Cow::Borrowed(f)));
+        }
+        let body = &input[1..];
+        let mut owned: Option<Vec<u8>> = None;
+        let mut start = 0;
+        let mut i = 0;
+        loop {
+            let Some(c) = body.get(i) else {
+                return Err(PErr::msg(input, "unterminated quoted field").set_eod());
+            };
+            let escaped = if *c == self.quote && body.get(i+1) == Some(&self.quote) { true }
+                else if *c == self.quote { break; }
+                else { Some(*c) == self.escape && i+1 < body.len() };
+            if escaped {
+                owned.get_or_insert_with(Vec::new).extend_from_slice(&body[start..i]);
+                start = i+1;
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+        let rest = &body[i+1..];
+        let f = match owned {
+            None        => Cow::Borrowed(&body[..i]),
+            Some(mut x) => { x.extend_from_slice(&body[start..i]); Cow::Owned(x) },
+        };
+        Ok((rest, f))
+    }
+}
+
+/// iterator of records, stops after the first error
+pub struct Reader<'a> {
+    csv: Csv,
+    input: &'a [u8],
+    header: Option<Record<'a>>,
+    header_error: Option<PErr<'a,u8>>,
+    error: bool,
+}
+
+impl<'a> Reader<'a> {
+    /// header record if `Csv::header(true)`, `None` if there is no input or the header is an error
+    pub fn header(&self) -> Option<&[Cow<'a,[u8]>]> { self.header.as_deref() }
+
+    /// index of the column by header name
+    pub fn column(&self, name: &[u8]) -> Option<usize> {
+        self.header.as_ref()?.iter().position(|x| x.as_ref() == name)
+    }
+
+    /// input not read yet
+    pub fn remaining(&self) -> &'a [u8] { self.input }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = Result<Record<'a>,PErr<'a,u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.header_error.take() { self.error = true; return Some(Err(e)); }
+        if self.error || self.input.is_empty() { return None; }
+        match self.csv.record().parse(self.input) {
+            Ok((input, r)) => { self.input = input; Some(Ok(r)) },
+            Err(e)         => { self.error = true; Some(Err(e)) },
+        }
+    }
+}
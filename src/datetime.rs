@@ -0,0 +1,318 @@
+//! Date and time in text: RFC 3339, ISO 8601, RFC 2822 and HTTP-date, RFC 9110.
+//! Results are plain structs, `DateTime::unix_timestamp` converts without a time crate.
+//! Fields are range checked: month, day of the month with leap years, hour, minute, second
+//! and offset; second 60 is a leap second and allowed at 23:59:60 UTC only.
+//! Day names are not checked against the date.
+//!
+//! ```text
+//! let (_, t) = rfc3339(b"1985-04-12T23:20:50.52Z")?;
+//! let (_, t) = http_date(b"Sun, 06 Nov 1994 08:49:37 GMT")?;
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::u8::is_dec_digit;
+use std::fmt;
+
+const MONTHS: [&[u8]; 12] = [b"jan", b"feb", b"mar", b"apr", b"may", b"jun", b"jul", b"aug", b"sep", b"oct", b"nov", b"dec"];
+const DAYS: [&[u8]; 7] = [b"monday", b"tuesday", b"wednesday", b"thursday", b"friday", b"saturday", b"sunday"];
+
+/// calendar date
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// `None` if month or day is out of range
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        (1..=12).contains(&month).then_some(())?;
+        (1..=days_in_month(year, month)).contains(&day).then_some(Date { year, month, day })
+    }
+
+    /// days since 1970-01-01
+    pub fn days_since_epoch(&self) -> i64 {
+        // days from civil, proleptic Gregorian calendar
+        let m = i64::from(self.month);
+        let y = i64::from(self.year) - i64::from(m <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era*400;
+        let doy = (153*(m + if m > 2 { -3 } else { 9 }) + 2)/5 + i64::from(self.day) - 1;
+        let doe = yoe*365 + yoe/4 - yoe/100 + doy;
+        era*146097 + doe - 719468
+    }
+
+    /// ISO weekday, 1 is Monday, 7 is Sunday
+    pub fn weekday(&self) -> u8 { ((self.days_since_epoch() + 3).rem_euclid(7) + 1) as u8 }
+}
+
+/// time of day
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash,Default)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    /// 60 is a leap second
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+/// date and time
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+    /// offset from UTC in minutes, `None` if unknown: `-00:00`, `-0000` or local time of ISO 8601
+    pub offset: Option<i16>,
+}
+
+impl DateTime {
+    /// seconds since 1970-01-01T00:00:00Z, unknown offset is UTC, leap second is the next second
+    pub fn unix_timestamp(&self) -> i64 {
+        let t = &self.time;
+        self.date.days_since_epoch()*86400 + i64::from(t.hour)*3600 + i64::from(t.minute)*60 + i64::from(t.second)
+            - i64::from(self.offset.unwrap_or(0))*60
+    }
+}
+
+/// RFC 3339
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (d, t) = (&self.date, &self.time);
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", d.year, d.month, d.day, t.hour, t.minute, t.second)?;
+        if t.nanosecond > 0 { write!(f, ".{}", format!("{:09}", t.nanosecond).trim_end_matches('0'))?; }
+        match self.offset {
+            None    => f.write_str("-00:00"),
+            Some(0) => f.write_str("Z"),
+            Some(x) => write!(f, "{}{:02}:{:02}", if x < 0 { '-' } else { '+' }, x.abs()/60, x.abs()%60),
+        }
+    }
+}
+
+pub fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2                       => 28,
+        4 | 6 | 9 | 11          => 30,
+        _                       => 31,
+    }
+}
+
+/// `n` digits
+fn num<'a>(input: &'a [u8], n: usize, what: &'static str) -> ParseResult<'a,u8,u32> {
+    let (rest, d) = expected(seq_exact(is_dec_digit, n), what).parse(input)?;
+    Ok((rest, d.iter().fold(0, |a, c| a*10 + u32::from(c - b'0'))))
+}
+
+/// `n` digits not over `max`
+fn field<'a>(input: &'a [u8], n: usize, max: u32, what: &'static str, msg: &'static str) -> ParseResult<'a,u8,u32> {
+    let (rest, x) = num(input, n, what)?;
+    if x > max { return Err(PErr::msg(input, msg)); }
+    Ok((rest, x))
+}
+
+fn sep<'a>(input: &'a [u8], s: &'static [u8]) -> Result<&'a [u8], PErr<'a,u8>> {
+    Ok(expected(starts_with(s), "separator").parse(input)?.0)
+}
+
+fn ws(input: &[u8]) -> &[u8] {
+    &input[input.iter().take_while(|x| matches!(x, b' ' | b'\t' | b'\r' | b'\n')).count()..]
+}
+
+/// date `YYYY<s>MM<s>DD`
+fn date_sep<'a>(input: &'a [u8], s: &'static [u8]) -> ParseResult<'a,u8,Date> {
+    let (i, year) = num(input, 4, "year")?;
+    let m = sep(i, s)?;
+    let (i, month) = num(m, 2, "month")?;
+    let d = sep(i, s)?;
+    let (i, day) = num(d, 2, "day")?;
+    Ok((i, make_date(year, (m, month), (d, day))?))
+}
+
+fn make_date<'a>(year: u32, month: (&'a [u8], u32), day: (&'a [u8], u32)) -> Result<Date, PErr<'a,u8>> {
+    if !(1..=12).contains(&month.1) { return Err(PErr::msg(month.0, "month is out of range")); }
+    Date::new(year as u16, month.1 as u8, day.1.min(99) as u8).ok_or_else(|| PErr::msg(day.0, "day is out of range"))
+}
+
+/// time `hh<s>mm<s>ss` with fraction if `frac` is a separator of it
+fn time_sep<'a>(input: &'a [u8], s: &'static [u8], frac: &'static [u8]) -> ParseResult<'a,u8,Time> {
+    let (i, hour) = field(input, 2, 23, "hour", "hour is out of range")?;
+    let (i, minute) = field(sep(i, s)?, 2, 59, "minute", "minute is out of range")?;
+    let (i, second) = field(sep(i, s)?, 2, 60, "second", "second is out of range")?;
+    let mut t = Time { hour: hour as u8, minute: minute as u8, second: second as u8, nanosecond: 0 };
+    let i = match i.split_first() {
+        Some((c, f)) if frac.contains(c) => {
+            let (i, digits) = expected(seq(is_dec_digit), "fraction of second").parse(f)?;
+            // digits over nanoseconds are dropped
+            t.nanosecond = digits.iter().chain(std::iter::repeat(&b'0')).take(9).fold(0, |a, c| a*10 + u32::from(c - b'0'));
+            i
+        },
+        _ => i,
+    };
+    Ok((i, t))
+}
+
+fn make_datetime(input: &[u8], date: Date, time: Time, offset: Option<i16>) -> Result<DateTime, PErr<'_,u8>> {
+    if time.second == 60 {
+        let utc = (i32::from(time.hour)*60 + i32::from(time.minute) - i32::from(offset.unwrap_or(0))).rem_euclid(1440);
+        if utc != 1439 { return Err(PErr::msg(input, "leap second is not at 23:59:60 UTC")); }
+    }
+    Ok(DateTime { date, time, offset })
+}
+
+/// `+hh<s>mm` or `-hh<s>mm` in minutes, `-00<s>00` is unknown offset
+fn numeric_offset<'a>(input: &'a [u8], s: &'static [u8], minutes_optional: bool) -> ParseResult<'a,u8,Option<i16>> {
+    let (i, sign) = expected(any(b"+-"), "offset").parse(input)?;
+    let (i, h) = field(i, 2, 23, "offset hour", "offset is out of range")?;
+    let minutes = !minutes_optional || if s.is_empty() { i.first().is_some_and(is_dec_digit) } else { i.starts_with(s) };
+    let (i, m) = if minutes { field(sep(i, s)?, 2, 59, "offset minute", "offset is out of range")? } else { (i, 0) };
+    let x = (h*60 + m) as i16;
+    Ok((i, match sign { b"-" if x == 0 => None, b"-" => Some(-x), _ => Some(x) }))
+}
+
+/// parser of full-date `YYYY-MM-DD`
+pub fn date(input: &[u8]) -> ParseResult<'_,u8,Date> { date_sep(input, b"-") }
+
+/// parser of partial-time `hh:mm:ss[.frac]`
+pub fn time(input: &[u8]) -> ParseResult<'_,u8,Time> { time_sep(input, b":", b".") }
+
+/// parser of RFC 3339 date-time `YYYY-MM-DDThh:mm:ss[.frac](Z|+hh:mm)`, `t`, `z` and space
+/// in place of `T` are accepted
+pub fn rfc3339(input: &[u8]) -> ParseResult<'_,u8,DateTime> {
+    let (i, d) = date(input)?;
+    let (i, _) = expected(any(b"Tt "), "`T`").parse(i)?;
+    let (i, t) = time(i)?;
+    let (i, offset) = match i.first() {
+        Some(b'Z' | b'z') => (&i[1..], Some(0)),
+        _                 => numeric_offset(i, b":", false)?,
+    };
+    Ok((i, make_datetime(input, d, t, offset)?))
+}
+
+/// parser of ISO 8601 date and time, extended `YYYY-MM-DDThh:mm:ss` or basic `YYYYMMDDThhmmss` format,
+/// fraction with `.` or `,`, offset `Z`, `+hh`, `+hhmm` or `+hh:mm`, no offset is local time
+pub fn iso8601(input: &[u8]) -> ParseResult<'_,u8,DateTime> {
+    let extended = input.get(4) == Some(&b'-');
+    let (ds, ts): (&[u8], &[u8]) = if extended { (b"-", b":") } else { (b"", b"") };
+    let (i, d) = date_sep(input, ds)?;
+    let (i, _) = expected(any(b"T"), "`T`").parse(i)?;
+    let (i, t) = time_sep(i, ts, b".,")?;
+    let (i, offset) = match i.first() {
+        Some(b'Z')        => (&i[1..], Some(0)),
+        Some(b'+' | b'-') => numeric_offset(i, ts, true)?,
+        _                 => (i, None),
+    };
+    Ok((i, make_datetime(input, d, t, offset)?))
+}
+
+/// 3 letters of a name from the list, case-insensitive
+fn short_name<'a>(input: &'a [u8], names: &[&[u8]], what: &'static str) -> ParseResult<'a,u8,u8> {
+    let n = input.get(..3).and_then(|x| names.iter().position(|m| m[..3].eq_ignore_ascii_case(x)));
+    match n {
+        Some(n) => Ok((&input[3..], n as u8 + 1)),
+        None    => Err(PErr::new(input).expected_push(Msg::Str(what)).fmt_str()),
+    }
+}
+
+/// full day name, case-insensitive
+fn long_day_name(input: &[u8]) -> ParseResult<'_,u8,u8> {
+    for (n, d) in DAYS.iter().enumerate() {
+        if input.get(..d.len()).is_some_and(|x| x.eq_ignore_ascii_case(d)) { return Ok((&input[d.len()..], n as u8 + 1)); }
+    }
+    Err(PErr::new(input).expected_push(Msg::Str("day name")).fmt_str())
+}
+
+/// RFC 2822 zone, obsolete names of US zones, military zones are unknown offset
+fn zone(input: &[u8]) -> ParseResult<'_,u8,Option<i16>> {
+    if matches!(input.first(), Some(b'+' | b'-')) { return numeric_offset(input, b"", false); }
+    let n = input.iter().take_while(|x| x.is_ascii_alphabetic()).count();
+    let h = match input[..n].to_ascii_uppercase().as_slice() {
+        b"UT" | b"GMT" => Some(0),
+        b"EDT"         => Some(-4),
+        b"EST" | b"CDT" => Some(-5),
+        b"CST" | b"MDT" => Some(-6),
+        b"MST" | b"PDT" => Some(-7),
+        b"PST"         => Some(-8),
+        [x] if *x != b'J' => None,
+        _ => return Err(PErr::new(input).expected_push(Msg::Str("zone")).fmt_str()),
+    };
+    Ok((&input[n..], h.map(|x| x*60)))
+}
+
+/// parser of RFC 2822 date-time `[Fri, ]21 Nov 1997 09:55:06 -0600`, obsolete 2 or 3 digits year,
+/// zone names and a trailing comment are accepted
+pub fn rfc2822(input: &[u8]) -> ParseResult<'_,u8,DateTime> {
+    let mut i = ws(input);
+    if i.first().is_some_and(u8::is_ascii_alphabetic) {
+        let (next, _) = short_name(i, &DAYS, "day name")?;
+        i = sep(ws(next), b",")?;
+    }
+    let i = ws(i);
+    let n = i.iter().take_while(|x| is_dec_digit(x)).count().clamp(1, 2);
+    let (d, (i, day)) = (i, num(i, n, "day")?);
+    let (i, month) = short_name(ws(i), &MONTHS, "month")?;
+    let i = ws(i);
+    let n = i.iter().take_while(|x| is_dec_digit(x)).count().clamp(2, 4);
+    let (i, year) = num(i, n, "year")?;
+    let year = match n { 2 if year < 50 => year + 2000, 2 | 3 => year + 1900, _ => year };
+    let date = make_date(year, (i, u32::from(month)), (d, day))?;
+    let i = ws(i);
+    let (i, hour) = field(i, 2, 23, "hour", "hour is out of range")?;
+    let (i, minute) = field(sep(i, b":")?, 2, 59, "minute", "minute is out of range")?;
+    let (i, second) = match i.strip_prefix(b":") {
+        Some(s) => field(s, 2, 60, "second", "second is out of range")?,
+        None    => (i, 0),
+    };
+    let time = Time { hour: hour as u8, minute: minute as u8, second: second as u8, nanosecond: 0 };
+    let (i, offset) = zone(ws(i))?;
+    let rest = ws(i);
+    let i = match rest.strip_prefix(b"(") {
+        Some(c) => c.iter().position(|x| *x == b')').map_or(i, |n| &c[n+1..]),
+        None    => i,
+    };
+    Ok((i, make_datetime(input, date, time, offset)?))
+}
+
+/// parser of HTTP-date: IMF-fixdate `Sun, 06 Nov 1994 08:49:37 GMT`,
+/// obsolete RFC 850 `Sunday, 06-Nov-94 08:49:37 GMT` and asctime `Sun Nov  6 08:49:37 1994`;
+/// 2 digits year under 50 is 20xx
+pub fn http_date(input: &[u8]) -> ParseResult<'_,u8,DateTime> {
+    let name = input.iter().take_while(|x| x.is_ascii_alphabetic()).count();
+    let (i, date, time) = match input.get(name) {
+        Some(b',') if name == 3 => {
+            let (i, _) = short_name(input, &DAYS, "day name")?;
+            let d = sep(sep(i, b",")?, b" ")?;
+            let (i, day) = num(d, 2, "day")?;
+            let (m, month) = short_name(sep(i, b" ")?, &MONTHS, "month")?;
+            let (i, year) = num(sep(m, b" ")?, 4, "year")?;
+            let date = make_date(year, (m, u32::from(month)), (d, day))?;
+            let (i, time) = time_sep(sep(i, b" ")?, b":", b"")?;
+            (sep(sep(i, b" ")?, b"GMT")?, date, time)
+        },
+        Some(b',') => {
+            let (i, _) = long_day_name(input)?;
+            let d = sep(sep(i, b",")?, b" ")?;
+            let (i, day) = num(d, 2, "day")?;
+            let (m, month) = short_name(sep(i, b"-")?, &MONTHS, "month")?;
+            let (i, year) = num(sep(m, b"-")?, 2, "year")?;
+            let year = if year < 50 { year + 2000 } else { year + 1900 };
+            let date = make_date(year, (m, u32::from(month)), (d, day))?;
+            let (i, time) = time_sep(sep(i, b" ")?, b":", b"")?;
+            (sep(sep(i, b" ")?, b"GMT")?, date, time)
+        },
+        _ => {
+            let (i, _) = short_name(input, &DAYS, "day name")?;
+            let (m, month) = short_name(sep(i, b" ")?, &MONTHS, "month")?;
+            let d = sep(m, b" ")?;
+            let (i, day) = match d.strip_prefix(b" ") { Some(x) => num(x, 1, "day")?, None => num(d, 2, "day")? };
+            let (i, time) = time_sep(sep(i, b" ")?, b":", b"")?;
+            let (i, year) = num(sep(i, b" ")?, 4, "year")?;
+            (i, make_date(year, (m, u32::from(month)), (d, day))?, time)
+        },
+    };
+    Ok((i, make_datetime(input, date, time, Some(0))?))
+}
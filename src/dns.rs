@@ -0,0 +1,278 @@
+//! DNS messages, RFC 1035.
+//! Header, questions and resource records of common types, unknown types keep raw data.
+//! Compressed names are read from the whole message by `context::Source`,
+//! a pointer must point before the previous one, so pointer loops are an error.
+//! Names and record data borrow the message.
+//!
+//! ```text
+//! let msg = dns::parse(udp_payload)?;
+//! for rr in &msg.answers { if let RData::A(ip) = rr.data { ... } }
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::context::{*};
+use crate::u8::{*};
+use std::net::{Ipv4Addr,Ipv6Addr};
+use std::fmt;
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_NS: u16 = 2;
+pub const TYPE_CNAME: u16 = 5;
+pub const TYPE_SOA: u16 = 6;
+pub const TYPE_PTR: u16 = 12;
+pub const TYPE_MX: u16 = 15;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+pub const TYPE_SRV: u16 = 33;
+
+pub const CLASS_IN: u16 = 1;
+
+/// max length of name in wire format
+pub const MAX_NAME_LEN: usize = 255;
+/// max length of label
+pub const MAX_LABEL_LEN: usize = 63;
+
+type Src<'a> = Source<'a,u8>;
+
+/// message header
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Header {
+    pub id: u16,
+    pub flags: u16,
+    pub qdcount: u16,
+    pub ancount: u16,
+    pub nscount: u16,
+    pub arcount: u16,
+}
+
+impl Header {
+    /// response
+    pub fn qr(&self) -> bool { self.flags & 0x8000 != 0 }
+    pub fn opcode(&self) -> u8 { (self.flags >> 11 & 0xf) as u8 }
+    /// authoritative answer
+    pub fn aa(&self) -> bool { self.flags & 0x0400 != 0 }
+    /// truncated
+    pub fn tc(&self) -> bool { self.flags & 0x0200 != 0 }
+    /// recursion desired
+    pub fn rd(&self) -> bool { self.flags & 0x0100 != 0 }
+    /// recursion available
+    pub fn ra(&self) -> bool { self.flags & 0x0080 != 0 }
+    pub fn rcode(&self) -> u8 { (self.flags & 0xf) as u8 }
+}
+
+/// domain name, labels without the root label
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct Name<'a>(pub Vec<&'a [u8]>);
+
+impl<'a> Name<'a> {
+    pub fn labels(&self) -> &[&'a [u8]] { &self.0 }
+    pub fn is_root(&self) -> bool { self.0.is_empty() }
+}
+
+/// dotted name, root is `.`
+impl fmt::Display for Name<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() { return f.write_str("."); }
+        for (i, l) in self.0.iter().enumerate() {
+            if i > 0 { f.write_str(".")?; }
+            f.write_str(&String::from_utf8_lossy(l))?;
+        }
+        Ok(())
+    }
+}
+
+/// question
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Question<'a> {
+    pub name: Name<'a>,
+    pub qtype: u16,
+    pub qclass: u16,
+}
+
+/// data of resource record
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum RData<'a> {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Ns(Name<'a>),
+    Cname(Name<'a>),
+    Ptr(Name<'a>),
+    Mx { preference: u16, exchange: Name<'a> },
+    /// character strings
+    Txt(Vec<&'a [u8]>),
+    Soa { mname: Name<'a>, rname: Name<'a>, serial: u32, refresh: u32, retry: u32, expire: u32, minimum: u32 },
+    Srv { priority: u16, weight: u16, port: u16, target: Name<'a> },
+    /// other types and classes, raw data
+    Unknown(&'a [u8]),
+}
+
+/// resource record
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct ResourceRecord<'a> {
+    pub name: Name<'a>,
+    pub rtype: u16,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: RData<'a>,
+}
+
+/// message
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Message<'a> {
+    pub header: Header,
+    pub questions: Vec<Question<'a>>,
+    pub answers: Vec<ResourceRecord<'a>>,
+    pub authorities: Vec<ResourceRecord<'a>>,
+    pub additionals: Vec<ResourceRecord<'a>>,
+}
+
+/// parse a whole message, e.g. UDP payload
+pub fn parse(input: &[u8]) -> Result<Message<'_>, ParseError> {
+    message.parse_all(input)
+}
+
+/// parser of message, the input must start at the message
+pub fn message(input: &[u8]) -> ParseResult<'_,u8,Message<'_>> {
+    let mut src = Source::new(input);
+    message_ctx(input, &mut src)
+}
+
+/// parser of message with two bytes length prefix, DNS over TCP
+pub fn tcp_message(input: &[u8]) -> ParseResult<'_,u8,Message<'_>> {
+    let (rest, data) = take_record_be_u16(input)?;
+    let (_, m) = left(message, data_end).parse(data)?;
+    Ok((rest, m))
+}
+
+/// parser of header
+pub fn header(input: &[u8]) -> ParseResult<'_,u8,Header> {
+    let (input, (id, flags, qdcount, ancount, nscount, arcount)) =
+        tuple((be_u16, be_u16, be_u16, be_u16, be_u16, be_u16)).parse(input)?;
+    Ok((input, Header { id, flags, qdcount, ancount, nscount, arcount }))
+}
+
+/// parser big endian u32
+fn be_u32(input: &[u8]) -> ParseResult<'_,u8,u32> {
+    fmap(take_len_be_u32, |x| x as u32).parse(input)
+}
+
+/// context parser of message, state is the whole message
+pub fn message_ctx<'a>(input: &'a [u8], src: &mut Src<'a>) -> ParseResult<'a,u8,Message<'a>> {
+    let (mut input, header) = header(input)?;
+    let mut questions = Vec::new();
+    for _ in 0..header.qdcount {
+        let (next_input, q) = question(input, src)?;
+        questions.push(q);
+        input = next_input;
+    }
+    let mut sections: [Vec<ResourceRecord<'a>>; 3] = Default::default();
+    for (section, n) in sections.iter_mut().zip([header.ancount, header.nscount, header.arcount]) {
+        for _ in 0..n {
+            let (next_input, rr) = resource_record(input, src)?;
+            section.push(rr);
+            input = next_input;
+        }
+    }
+    let [answers, authorities, additionals] = sections;
+    Ok((input, Message { header, questions, answers, authorities, additionals }))
+}
+
+/// context parser of name with decompression
+pub fn name<'a>(input: &'a [u8], src: &mut Src<'a>) -> ParseResult<'a,u8,Name<'a>> {
+    let mut labels = Vec::new();
+    let mut len = 1;
+    let mut pos = input;
+    let mut rest = None;
+    // pointers must go strictly backward, it ends any loop
+    let mut limit = src.offset(input).ok_or_else(|| PErr::msg(input, "name is out of the message"))?;
+    loop {
+        let (next, l) = take_len_be_u8(pos)?;
+        match l {
+            0 => return Ok((rest.unwrap_or(next), Name(labels))),
+            1..=MAX_LABEL_LEN => {
+                let (next, label) = take_record(next, l)?;
+                len += l+1;
+                if len > MAX_NAME_LEN { return Err(PErr::msg(input, "name is too long")); }
+                labels.push(label);
+                pos = next;
+            },
+            0xc0.. => {
+                let (next, lo) = take_len_be_u8(next)?;
+                let ptr = (l & 0x3f) << 8 | lo;
+                if ptr >= limit { return Err(PErr::msg(pos, "bad compression pointer")); }
+                rest.get_or_insert(next);
+                limit = ptr;
+                pos = src.at(ptr).ok_or_else(|| PErr::msg(pos, "bad compression pointer"))?;
+            },
+            _ => return Err(PErr::msg(pos, "unknown label type")),
+        }
+    }
+}
+
+/// context parser of question
+pub fn question<'a>(input: &'a [u8], src: &mut Src<'a>) -> ParseResult<'a,u8,Question<'a>> {
+    let (input, name) = name(input, src)?;
+    let (input, (qtype, qclass)) = pair(be_u16, be_u16).parse(input)?;
+    Ok((input, Question { name, qtype, qclass }))
+}
+
+/// context parser of resource record
+pub fn resource_record<'a>(input: &'a [u8], src: &mut Src<'a>) -> ParseResult<'a,u8,ResourceRecord<'a>> {
+    let (input, name) = name(input, src)?;
+    let (input, (rtype, class, ttl)) = tuple((be_u16, be_u16, be_u32)).parse(input)?;
+    let (input, rdata) = take_record_be_u16(input)?;
+    // address types are defined for class IN only
+    let data = if class != CLASS_IN && matches!(rtype, TYPE_A | TYPE_AAAA) { RData::Unknown(rdata) }
+        else { rdata_ctx(rtype, rdata, src)? };
+    Ok((input, ResourceRecord { name, rtype, class, ttl, data }))
+}
+
+/// record data must be read as a whole, short data inside a complete record is not eod
+fn rdata_ctx<'a>(rtype: u16, rdata: &'a [u8], src: &mut Src<'a>) -> Result<RData<'a>, PErr<'a,u8>> {
+    let bad = || PErr::msg(rdata, "bad record data");
+    let data = |src: &mut Src<'a>| rdata_parts(rtype, rdata, src);
+    match data(src) {
+        Ok(([], x))            => Ok(x),
+        Err(e) if !e.is_eod()  => Err(e),
+        _                      => Err(bad()),
+    }
+}
+
+fn rdata_parts<'a>(rtype: u16, rdata: &'a [u8], src: &mut Src<'a>) -> ParseResult<'a,u8,RData<'a>> {
+    let bad = || PErr::msg(rdata, "bad record data");
+    let (rest, data) = match rtype {
+        TYPE_A    => (&rdata[rdata.len().min(4)..], RData::A(<[u8;4]>::try_from(rdata).map_err(|_| bad())?.into())),
+        TYPE_AAAA => (&rdata[rdata.len().min(16)..], RData::Aaaa(<[u8;16]>::try_from(rdata).map_err(|_| bad())?.into())),
+        TYPE_NS    => { let (r, n) = name(rdata, src)?; (r, RData::Ns(n)) },
+        TYPE_CNAME => { let (r, n) = name(rdata, src)?; (r, RData::Cname(n)) },
+        TYPE_PTR   => { let (r, n) = name(rdata, src)?; (r, RData::Ptr(n)) },
+        TYPE_MX => {
+            let (r, preference) = be_u16(rdata)?;
+            let (r, exchange) = name(r, src)?;
+            (r, RData::Mx { preference, exchange })
+        },
+        TYPE_TXT => {
+            let mut r = rdata;
+            let mut strings = Vec::new();
+            while !r.is_empty() {
+                let (next, s) = take_record_be_u8(r)?;
+                strings.push(s);
+                r = next;
+            }
+            (r, RData::Txt(strings))
+        },
+        TYPE_SOA => {
+            let (r, mname) = name(rdata, src)?;
+            let (r, rname) = name(r, src)?;
+            let (r, (serial, refresh, retry, expire, minimum)) = tuple((be_u32, be_u32, be_u32, be_u32, be_u32)).parse(r)?;
+            (r, RData::Soa { mname, rname, serial, refresh, retry, expire, minimum })
+        },
+        TYPE_SRV => {
+            let (r, (priority, weight, port)) = tuple((be_u16, be_u16, be_u16)).parse(rdata)?;
+            let (r, target) = name(r, src)?;
+            (r, RData::Srv { priority, weight, port, target })
+        },
+        _ => (&rdata[rdata.len()..], RData::Unknown(rdata)),
+    };
+    Ok((rest, data))
+}
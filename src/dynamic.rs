@@ -0,0 +1,110 @@
+//! Dynamic parsers.
+//! `Parser` is `Copy` and returns `impl Trait`, so it is not object-safe.
+//! `DynParser` is the object-safe side: every parser is a `DynParser`,
+//! boxed parsers are `Parser` by reference, as `&ClassOfSymbols` is.
+//! It allows to keep heterogeneous parsers in a `Vec` and build grammars at runtime.
+
+use crate::parser_combinators::{*};
+use std::{rc::Rc,sync::Arc};
+
+/// Object-safe parser
+pub trait DynParser<'a,I:'a,O> {
+    fn parse_dyn(&self, input:&'a [I]) -> ParseResult<'a,I,O>;
+}
+
+impl<'a,I:'a,O,P> DynParser<'a,I,O> for P
+where
+    P: Parser<'a,I,O>,
+{
+    fn parse_dyn(&self, input:&'a [I]) -> ParseResult<'a,I,O> { self.parse(input) }
+}
+
+/// boxed parser, `&BoxParser` is a `Parser`
+pub struct BoxParser<'a,I,O>(Box<dyn DynParser<'a,I,O>+'a>);
+
+impl<'a,I:'a,O> BoxParser<'a,I,O> {
+    pub fn new<P:DynParser<'a,I,O>+'a>(p:P) -> Self { BoxParser(Box::new(p)) }
+}
+
+impl<'a,I:'a,O> DynParser<'a,I,O> for BoxParser<'a,I,O> {
+    fn parse_dyn(&self, input:&'a [I]) -> ParseResult<'a,I,O> { self.0.parse_dyn(input) }
+}
+
+impl<'a,I:'a,O> Parser<'a,I,O> for &BoxParser<'a,I,O> {
+    fn parse(&self, input:&'a [I]) -> ParseResult<'a,I,O> { self.0.parse_dyn(input) }
+}
+
+/// shared parser, `&RcParser` is a `Parser`
+#[derive(Clone)]
+pub struct RcParser<'a,I,O>(Rc<dyn DynParser<'a,I,O>+'a>);
+
+impl<'a,I:'a,O> RcParser<'a,I,O> {
+    pub fn new<P:DynParser<'a,I,O>+'a>(p:P) -> Self { RcParser(Rc::new(p)) }
+}
+
+impl<'a,I:'a,O> DynParser<'a,I,O> for RcParser<'a,I,O> {
+    fn parse_dyn(&self, input:&'a [I]) -> ParseResult<'a,I,O> { self.0.parse_dyn(input) }
+}
+
+impl<'a,I:'a,O> Parser<'a,I,O> for &RcParser<'a,I,O> {
+    fn parse(&self, input:&'a [I]) -> ParseResult<'a,I,O> { self.0.parse_dyn(input) }
+}
+
+/// thread safe shared parser, `&ArcParser` is a `Parser`
+#[derive(Clone)]
+pub struct ArcParser<'a,I,O>(Arc<dyn DynParser<'a,I,O>+Send+Sync+'a>);
+
+impl<'a,I:'a,O> ArcParser<'a,I,O> {
+    pub fn new<P:DynParser<'a,I,O>+Send+Sync+'a>(p:P) -> Self { ArcParser(Arc::new(p)) }
+}
+
+impl<'a,I:'a,O> DynParser<'a,I,O> for ArcParser<'a,I,O> {
+    fn parse_dyn(&self, input:&'a [I]) -> ParseResult<'a,I,O> { self.0.parse_dyn(input) }
+}
+
+impl<'a,I:'a,O> Parser<'a,I,O> for &ArcParser<'a,I,O> {
+    fn parse(&self, input:&'a [I]) -> ParseResult<'a,I,O> { self.0.parse_dyn(input) }
+}
+
+/// alt combinator over a slice of parsers, no tuple-size limit
+pub fn alt_dyn<'a,'b,I:'a,O,D>(ps:&'b [D]) -> impl Parser<'a,I,O>+'b
+where
+    D: DynParser<'a,I,O>,
+{
+    move |input:&'a[I]| {
+        let mut er = PErr::new(input);
+        for p in ps {
+            match p.parse_dyn(input) {
+                Ok(r)  => return Ok(r),
+                Err(e) => er = e,
+            }
+        }
+        Err(er)
+    }
+}
+
+/// permut combinator over a slice of parsers, no tuple-size limit,
+/// results are in order of the slice
+pub fn permut_dyn<'a,'b,I:'a,O,D>(ps:&'b [D]) -> impl Parser<'a,I,Vec<O>>+'b
+where
+    D: DynParser<'a,I,O>,
+{
+    move |input:&'a[I]| {
+        let mut r: Vec<Option<O>> = ps.iter().map(|_| None).collect();
+        let mut count:usize = 0;
+        let mut new_input = input;
+        let mut er = PErr::new(input);
+        loop {
+            let count_old = count;
+            for (i, p) in ps.iter().enumerate() {
+                if r[i].is_some() { continue; }
+                match p.parse_dyn(new_input) {
+                    Ok((inp, x)) => { r[i] = Some(x); new_input = inp; count+=1; },
+                    Err(e)       => er = e,
+                }
+            }
+            if count == count_old || count == ps.len() { break; }
+        }
+        if count == ps.len() { Ok((new_input, r.into_iter().flatten().collect())) } else { Err(er) }
+    }
+}
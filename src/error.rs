@@ -0,0 +1,73 @@
+//! Owned parse error.
+//! `PErr` borrows the input, `ParseError` does not, so it can be returned from functions
+//! and sent across threads.
+
+use crate::parser_combinators::{PErr,Excerpt};
+use std::{fmt,error};
+
+/// Owned error, detached from the input lifetime
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct ParseError {
+    pub(crate) offset: Option<usize>,
+    pub(crate) remaining: usize,
+    pub(crate) excerpt: String,
+    user_msg: Vec<String>,
+    expected: Vec<String>,
+    end_of_data: bool,
+}
+
+impl ParseError {
+    /// offset of the error in the source, `None` if the source is unknown
+    pub fn offset(&self) -> Option<usize> { self.offset }
+    /// count of elements of the source left unparsed at the error
+    pub fn remaining(&self) -> usize { self.remaining }
+    /// beginning of the input at the error, formatted as `PErr` Display does
+    pub fn excerpt(&self) -> &str { &self.excerpt }
+    /// user messages, in order of pushing
+    pub fn get_msg(&self) -> &[String] { &self.user_msg }
+    /// what was expected at the place of the error
+    pub fn get_expected(&self) -> &[String] { &self.expected }
+    /// set true if reason error parsing  it is end of data
+    pub fn is_eod(&self) -> bool { self.end_of_data }
+}
+
+impl<'a,I:'a+Excerpt> PErr<'a,I> {
+    /// convert into owned error, `source` is the whole input of the parse
+    pub fn into_owned(self, source:&[I]) -> ParseError {
+        let mut r = ParseError::from(self);
+        r.offset = source.len().checked_sub(r.remaining);
+        r
+    }
+}
+
+/// the offset is unknown here, use `PErr::into_owned` if the source is at hand
+impl<'a,I:'a+Excerpt> From<PErr<'a,I>> for ParseError {
+    fn from(e: PErr<'a,I>) -> Self {
+        ParseError {
+            offset: None,
+            remaining: e.get_input().len(),
+            excerpt: e.excerpt(),
+            user_msg: e.get_msg().iter().map(|x| x.as_str().to_string()).collect(),
+            expected: e.get_expected().iter().map(|x| x.as_str().to_string()).collect(),
+            end_of_data: e.is_eod(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.offset {
+            Some(o) => writeln!(f, "Err at {}: {}", o, self.excerpt)?,
+            None    => writeln!(f, "Err: {}", self.excerpt)?,
+        }
+        for i in self.user_msg.iter().rev() {
+            writeln!(f, "{}", i)?;
+        }
+        if !self.expected.is_empty() {
+            writeln!(f, "expected: {}", self.expected.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ParseError {}
@@ -0,0 +1,289 @@
+//! HTTP/1.0 and HTTP/1.1 message heads, RFC 9112.
+//! Request line, status line, headers and chunked body, all slices point into the input.
+//! Incomplete input is an error with `is_eod()`, so the parsers fit `codec::FrameDecoder`.
+//! Line break is CRLF, single LF is accepted too.
+//!
+//! ```text
+//! let (body, req) = request(Limits::default()).parse(input)?;
+//! if req.headers.is_chunked() { let (rest, chunked) = chunked(Limits::default()).parse(body)?; }
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::u8::{is_dec_digit,is_hex_digit};
+use std::borrow::Cow;
+
+/// HTTP version
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Version {
+    Http10,
+    Http11,
+}
+
+/// header field, `value` is trimmed, with obs-fold it spans several lines
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Header<'a> {
+    pub name: &'a [u8],
+    pub value: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+    /// value with obs-fold line breaks replaced by a space
+    pub fn value_unfolded(&self) -> Cow<'a,[u8]> {
+        if !self.value.contains(&b'\n') { return Cow::Borrowed(self.value); }
+        let mut r = Vec::with_capacity(self.value.len());
+        let mut fold = false;
+        for c in self.value {
+            match c {
+                b'\r' | b'\n'         => fold = true,
+                b' ' | b'\t' if fold  => {},
+                _ => {
+                    if fold { r.push(b' '); fold = false; }
+                    r.push(*c);
+                },
+            }
+        }
+        Cow::Owned(r)
+    }
+}
+
+/// headers in order of the message
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct Headers<'a>(pub Vec<Header<'a>>);
+
+impl<'a> Headers<'a> {
+    /// first value of the header, name is case-insensitive
+    pub fn get(&self, name: &str) -> Option<&'a [u8]> {
+        self.0.iter().find(|x| x.name.eq_ignore_ascii_case(name.as_bytes())).map(|x| x.value)
+    }
+
+    /// all values of the header, name is case-insensitive
+    pub fn get_all<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'a [u8]> + 's {
+        self.0.iter().filter(move |x| x.name.eq_ignore_ascii_case(name.as_bytes())).map(|x| x.value)
+    }
+
+    pub fn content_length(&self) -> Option<usize> {
+        std::str::from_utf8(self.get("content-length")?).ok()?.parse().ok()
+    }
+
+    /// last transfer coding is chunked
+    pub fn is_chunked(&self) -> bool {
+        self.get_all("transfer-encoding")
+            .flat_map(|x| x.split(|c| *c == b','))
+            .last()
+            .is_some_and(|x| x.trim_ascii().eq_ignore_ascii_case(b"chunked"))
+    }
+
+    pub fn len(&self) -> usize { self.0.len() }
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+}
+
+/// request head
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Request<'a> {
+    pub method: &'a [u8],
+    pub target: &'a [u8],
+    pub version: Version,
+    pub headers: Headers<'a>,
+}
+
+/// response head
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Response<'a> {
+    pub version: Version,
+    pub status: u16,
+    pub reason: &'a [u8],
+    pub headers: Headers<'a>,
+}
+
+/// limits of message head
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Limits {
+    max_headers: usize,
+    max_head_len: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self { Limits { max_headers: 100, max_head_len: 64*1024 } }
+}
+
+impl Limits {
+    pub fn max_headers(mut self, n: usize) -> Self {
+        self.max_headers = n;
+        self
+    }
+
+    /// max size of head: start line, headers and empty line
+    pub fn max_head_len(mut self, n: usize) -> Self {
+        self.max_head_len = n;
+        self
+    }
+}
+
+/// chunked body
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct Chunked<'a> {
+    pub chunks: Vec<&'a [u8]>,
+    pub trailers: Headers<'a>,
+}
+
+impl<'a> Chunked<'a> {
+    /// body without chunk framing
+    pub fn concat(&self) -> Vec<u8> { self.chunks.concat() }
+}
+
+fn is_tchar(i: &u8) -> bool { i.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(i) }
+fn is_vchar(i: &u8) -> bool { *i > 0x20 && *i != 0x7f }
+
+/// parser of line break
+pub fn eol(input: &[u8]) -> ParseResult<'_,u8,&[u8]> {
+    match input {
+        [b'\r', b'\n', ..] => Ok(split_at_revers(input, 2)),
+        [b'\n', ..]        => Ok(split_at_revers(input, 1)),
+        [] | [b'\r']       => Err(PErr::new(input).set_eod()),
+        _                  => Err(PErr::new(input)),
+    }
+}
+
+/// count of leading elements satisfying predicate, may be zero
+fn take_while(input: &[u8], p: impl Fn(&u8) -> bool) -> (&[u8], &[u8]) {
+    split_at_revers(input, input.iter().take_while(|x| p(x)).count())
+}
+
+/// parser of `HTTP/1.0` or `HTTP/1.1`
+pub fn version(input: &[u8]) -> ParseResult<'_,u8,Version> {
+    alt((
+        fmap(starts_with(b"HTTP/1.1"), |_| Version::Http11),
+        fmap(starts_with(b"HTTP/1.0"), |_| Version::Http10),
+    )).parse(input).map_err(|e| e.expected_push(Msg::Str("HTTP/1.0 or HTTP/1.1")).fmt_str())
+}
+
+/// parser of request line `method SP target SP version CRLF`
+pub fn request_line(input: &[u8]) -> ParseResult<'_,u8,(&[u8],&[u8],Version)> {
+    let (input, (m, _, t, _, v, _)) = tuple((
+        expected(seq(is_tchar), "method"),
+        starts_with(b" "),
+        expected(seq(is_vchar), "request target"),
+        starts_with(b" "),
+        version,
+        expected(eol, "line break"),
+    )).parse(input)?;
+    Ok((input, (m, t, v)))
+}
+
+/// parser of status line `version SP 3DIGIT SP reason CRLF`
+pub fn status_line(input: &[u8]) -> ParseResult<'_,u8,(Version,u16,&[u8])> {
+    let (input, (v, _, s, _)) = tuple((
+        version,
+        starts_with(b" "),
+        expected(seq_exact(is_dec_digit, 3), "status code"),
+        expected(starts_with(b" "), "space"),
+    )).parse(input)?;
+    let (input, r) = take_while(input, |x| *x == b'\t' || *x >= 0x20 && *x != 0x7f);
+    let (input, _) = expected(eol, "line break").parse(input)?;
+    let s = s.iter().fold(0, |a, x| a*10 + u16::from(x - b'0'));
+    Ok((input, (v, s, r)))
+}
+
+/// parser of header field with line break
+pub fn header(input: &[u8]) -> ParseResult<'_,u8,Header<'_>> {
+    let (value, name) = left(expected(seq(is_tchar), "header name"), expected(starts_with(b":"), "`:`")).parse(input)?;
+    let mut i = 0;
+    let (end, rest) = loop {
+        match value.get(i) {
+            None          => return Err(PErr::new(input).set_eod()),
+            Some(b'\r') if i+1 == value.len() => return Err(PErr::new(input).set_eod()),
+            Some(b'\r') if value[i+1] == b'\n' => {},
+            Some(b'\n')   => {},
+            Some(c) if *c < 0x20 && *c != b'\t' || *c == 0x7f => {
+                return Err(PErr::msg(&value[i..], "control character in header value"));
+            },
+            Some(_)       => { i += 1; continue; },
+        }
+        let (rest, _) = eol(&value[i..])?;
+        match rest.first() {
+            None                => return Err(PErr::new(input).set_eod()),
+            Some(b' ' | b'\t')  => i = value.len()-rest.len(),
+            Some(_)             => break (i, rest),
+        }
+    };
+    Ok((rest, Header { name, value: value[..end].trim_ascii() }))
+}
+
+/// parser of headers and the empty line
+pub fn headers<'a>(limits: Limits) -> impl Parser<'a,u8,Headers<'a>> {
+    move |input: &'a [u8]| {
+        let mut r = Vec::new();
+        let mut input = input;
+        loop {
+            if let Ok((input, _)) = eol(input) { return Ok((input, Headers(r))); }
+            if input.is_empty() || input == b"\r" { return Err(PErr::new(input).set_eod()); }
+            if r.len() == limits.max_headers {
+                return Err(PErr::msg(input, "too many headers"));
+            }
+            let (next_input, h) = header(input)?;
+            r.push(h);
+            input = next_input;
+        }
+    }
+}
+
+/// check of the head size, incomplete head over the limit is not `is_eod()`
+fn limit<'a,O>(limits: Limits, input: &'a [u8], r: ParseResult<'a,u8,O>) -> ParseResult<'a,u8,O> {
+    let too_large = || Err(PErr::msg(input, "message head is too large"));
+    match r {
+        Ok((rest, _)) if input.len()-rest.len() > limits.max_head_len => too_large(),
+        Err(e) if e.is_eod() && input.len() > limits.max_head_len     => too_large(),
+        r => r,
+    }
+}
+
+/// parser of request head, the rest of input is body
+pub fn request<'a>(limits: Limits) -> impl Parser<'a,u8,Request<'a>> {
+    move |input: &'a [u8]| {
+        let r = pair(request_line, headers(limits)).parse(input)
+            .map(|(rest, ((method, target, version), headers))| (rest, Request { method, target, version, headers }));
+        limit(limits, input, r)
+    }
+}
+
+/// parser of response head, the rest of input is body
+pub fn response<'a>(limits: Limits) -> impl Parser<'a,u8,Response<'a>> {
+    move |input: &'a [u8]| {
+        let r = pair(status_line, headers(limits)).parse(input)
+            .map(|(rest, ((version, status, reason), headers))| (rest, Response { version, status, reason, headers }));
+        limit(limits, input, r)
+    }
+}
+
+/// parser of chunk size with extensions and line break
+fn chunk_size(input: &[u8]) -> ParseResult<'_,u8,usize> {
+    let (input, digits) = expected(seq(is_hex_digit), "chunk size").parse(input)?;
+    // checked before the line break, so a streamed size is rejected as soon as it is too large
+    let size = digits.iter().try_fold(0usize, |a, x| a.checked_mul(16)?.checked_add((*x as char).to_digit(16)? as usize))
+        .ok_or_else(|| PErr::msg(digits, "chunk size is too large"))?;
+    // chunk extensions are skipped
+    let (input, _) = take_while(input, |x| *x != b'\r' && *x != b'\n');
+    let (input, _) = eol(input)?;
+    Ok((input, size))
+}
+
+/// parser of chunked body: chunks, last chunk, trailers and the empty line,
+/// trailers have the limits of a head
+pub fn chunked<'a>(limits: Limits) -> impl Parser<'a,u8,Chunked<'a>> {
+    move |input: &'a [u8]| {
+        let mut r = Chunked::default();
+        let mut input = input;
+        loop {
+            let (next_input, size) = chunk_size(input)?;
+            if size == 0 {
+                let (next_input, trailers) = limit(limits, next_input, headers(limits).parse(next_input))?;
+                r.trailers = trailers;
+                return Ok((next_input, r));
+            }
+            let (next_input, data) = take_record(next_input, size)?;
+            let (next_input, _) = expected(eol, "line break").parse(next_input)?;
+            r.chunks.push(data);
+            input = next_input;
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! JSON, RFC 8259.
+//! Zero copy: strings without escapes and numbers borrow the input.
+//! Nesting of arrays and objects is limited, `parse` allows `MAX_DEPTH` levels.
+//!
+//! ```text
+//! let v = json::parse(br#"{"name":"parcelona","tags":["parser"]}"#)?;
+//! assert_eq!(Some("parcelona"), v.get("name").and_then(Value::as_str));
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::u8::{is_dec_digit,is_hex_digit};
+use std::borrow::Cow;
+
+/// default max nesting of arrays and objects
+pub const MAX_DEPTH: usize = 128;
+
+/// JSON value
+#[derive(Debug,Clone,PartialEq)]
+pub enum Value<'a> {
+    Null,
+    Bool(bool),
+    /// number as it is in the text
+    Number(&'a str),
+    String(Cow<'a,str>),
+    Array(Vec<Value<'a>>),
+    /// members in order of the text, duplicated names are kept
+    Object(Vec<(Cow<'a,str>, Value<'a>)>),
+}
+
+impl<'a> Value<'a> {
+    pub fn is_null(&self) -> bool { matches!(self, Value::Null) }
+    pub fn as_bool(&self) -> Option<bool> { if let Value::Bool(x) = self { Some(*x) } else { None } }
+    pub fn as_str(&self) -> Option<&str> { if let Value::String(x) = self { Some(x) } else { None } }
+    pub fn as_f64(&self) -> Option<f64> { if let Value::Number(x) = self { x.parse().ok() } else { None } }
+    /// number without fraction and exponent that fits in i64
+    pub fn as_i64(&self) -> Option<i64> { if let Value::Number(x) = self { x.parse().ok() } else { None } }
+    pub fn as_array(&self) -> Option<&[Value<'a>]> { if let Value::Array(x) = self { Some(x) } else { None } }
+    pub fn as_object(&self) -> Option<&[(Cow<'a,str>, Value<'a>)]> { if let Value::Object(x) = self { Some(x) } else { None } }
+    /// last member of object with the name
+    pub fn get(&self, name: &str) -> Option<&Value<'a>> {
+        self.as_object()?.iter().rev().find(|x| x.0 == name).map(|x| &x.1)
+    }
+}
+
+/// parse JSON text, whitespace around the value is allowed
+pub fn parse(input: &[u8]) -> Result<Value<'_>, ParseError> {
+    parse_depth(input, MAX_DEPTH)
+}
+
+/// parse JSON text with max nesting of arrays and objects
+pub fn parse_depth(input: &[u8], max_depth: usize) -> Result<Value<'_>, ParseError> {
+    left(value(max_depth), ws).parse_all(input)
+}
+
+#[inline]
+fn is_ws(i: &u8) -> bool { matches!(*i, b' ' | b'\t' | b'\n' | b'\r') }
+
+/// parser of optional whitespace
+pub fn ws(input: &[u8]) -> ParseResult<'_,u8,()> {
+    fmap(option(seq(is_ws)), |_| ()).parse(input)
+}
+
+/// parser of value with leading whitespace, `depth` is max nesting of arrays and objects
+pub fn value<'a>(depth: usize) -> impl Parser<'a,u8,Value<'a>> {
+    move |input: &'a [u8]| {
+        let (input, _) = ws(input)?;
+        match input.first() {
+            None                       => Err(PErr::new(input).set_eod().expected_push(Msg::Str("value"))),
+            Some(b'{' | b'[') if depth == 0 => Err(PErr::msg(input, "json nesting is too deep")),
+            Some(b'{')                 => fmap(object(depth-1), Value::Object).parse(input),
+            Some(b'[')                 => fmap(array(depth-1), Value::Array).parse(input),
+            Some(b'"')                 => fmap(string, Value::String).parse(input),
+            Some(b'-' | b'0'..=b'9')   => fmap(number, Value::Number).parse(input),
+            Some(b'n')                 => fmap(starts_with(b"null"), |_| Value::Null).parse(input),
+            Some(b't')                 => fmap(starts_with(b"true"), |_| Value::Bool(true)).parse(input),
+            Some(b'f')                 => fmap(starts_with(b"false"), |_| Value::Bool(false)).parse(input),
+            Some(_)                    => Err(PErr::new(input).expected_push(Msg::Str("value")).fmt_str()),
+        }
+    }
+}
+
+/// `open` item (`,` item)* `close` or `open` `close`, items take their whitespace
+fn list<'a,P,R>(open: &'static [u8], item: P, close: &'static [u8], what: &'static str) -> impl Parser<'a,u8,Vec<R>>
+where
+    P: Parser<'a,u8,R>,
+{
+    let items = fmap(pair(item, more_min(right(any(b","), item), 0)), |(first, mut v)| { v.insert(0, first); v });
+    // after the last item: `close`, or the error of the item after `,` that stopped the list
+    let end = alt((fmap(any(close), |_| ()), fmap(right(expected(any(b","), what), item), |_| ())));
+    let empty = fmap(any(close), |_| Vec::new());
+    right(pair(any(open), ws), alt((empty, left(items, end))))
+}
+
+/// `[` value (`,` value)* `]`
+fn array<'a>(depth: usize) -> impl Parser<'a,u8,Vec<Value<'a>>> {
+    list(b"[", left(value(depth), ws), b"]", "`,` or `]`")
+}
+
+/// `{` string `:` value (`,` string `:` value)* `}`
+fn object<'a>(depth: usize) -> impl Parser<'a,u8,Vec<(Cow<'a,str>, Value<'a>)>> {
+    let name = between(ws, expected(string, "member name"), pair(ws, expected(any(b":"), "`:`")));
+    list(b"{", pair(name, left(value(depth), ws)), b"}", "`,` or `}`")
+}
+
+/// parser of number, `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`
+pub fn number(input: &[u8]) -> ParseResult<'_,u8,&str> {
+    let int = alt((starts_with(b"0"), seq(is_dec_digit)));
+    let frac = pair(any(b"."), expected(seq(is_dec_digit), "digit"));
+    let exp = tuple((any(b"eE"), option(any(b"+-")), expected(seq(is_dec_digit), "digit")));
+    let (rest, r) = recognize(tuple((option(any(b"-")), expected(int, "digit"), option(frac), option(exp)))).parse(input)?;
+    let r = std::str::from_utf8(r).map_err(|_| PErr::new(input))?;
+    Ok((rest, r))
+}
+
+/// part of string content
+enum Part<'a> {
+    Raw(&'a str),
+    Escaped(char),
+}
+
+/// parser of string, borrows the input if there are no escapes
+pub fn string(input: &[u8]) -> ParseResult<'_,u8,Cow<'_,str>> {
+    let part = alt((fmap(raw, Part::Raw), fmap(escape, Part::Escaped)));
+    fmap(between(any(b"\""), more_min(part, 0), close_quote), |parts| match parts.as_slice() {
+        []               => Cow::Borrowed(""),
+        [Part::Raw(x)]   => Cow::Borrowed(*x),
+        _ => Cow::Owned(parts.iter().fold(String::new(), |mut r, x| {
+            match x { Part::Raw(x) => r.push_str(x), Part::Escaped(c) => r.push(*c) }
+            r
+        })),
+    }).parse(input)
+}
+
+/// characters without escapes
+fn raw(input: &[u8]) -> ParseResult<'_,u8,&str> {
+    let (rest, r) = seq(|c: &u8| *c >= 0x20 && *c != b'"' && *c != b'\\').parse(input)?;
+    Ok((rest, utf8(r)?))
+}
+
+/// `"` after the content, other input there is the error of the content
+fn close_quote(input: &[u8]) -> ParseResult<'_,u8,()> {
+    match input.first() {
+        None           => Err(PErr::new(input).set_eod().expected_push(Msg::Str("`\"`"))),
+        Some(b'\\')    => fmap(escape, |_| ()).parse(input),
+        Some(0..=0x1f) => Err(PErr::msg(input, "control character in string")),
+        Some(b'"')     => Ok((&input[1..], ())),
+        Some(_)        => fmap(raw, |_| ()).parse(input),
+    }
+}
+
+fn utf8(b: &[u8]) -> Result<&str, PErr<'_,u8>> {
+    std::str::from_utf8(b).map_err(|e| PErr::msg(&b[e.valid_up_to()..], "invalid utf-8 in string"))
+}
+
+/// escape sequence `\`...
+fn escape(input: &[u8]) -> ParseResult<'_,u8,char> {
+    if input.first() != Some(&b'\\') { return Err(PErr::new(input).expected_push(Msg::Str("`\\`")).fmt_str()); }
+    let c = match input.get(1) {
+        Some(b'"')  => '"',
+        Some(b'\\') => '\\',
+        Some(b'/')  => '/',
+        Some(b'b')  => '\x08',
+        Some(b'f')  => '\x0c',
+        Some(b'n')  => '\n',
+        Some(b'r')  => '\r',
+        Some(b't')  => '\t',
+        Some(b'u')  => {
+            let (rest, hi) = hex4(input)?;
+            if !(0xD800..0xE000).contains(&hi) { return Ok((rest, char::from_u32(hi).unwrap_or('\u{fffd}'))); }
+            let lone = || PErr::msg(input, "lone surrogate in string");
+            if hi >= 0xDC00 || !rest.starts_with(b"\\u") { return Err(lone()); }
+            let (rest, lo) = hex4(rest)?;
+            if !(0xDC00..0xE000).contains(&lo) { return Err(lone()); }
+            let c = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+            return Ok((rest, char::from_u32(c).ok_or_else(lone)?));
+        },
+        _ => return Err(PErr::msg(input, "invalid escape in string")),
+    };
+    Ok((&input[2..], c))
+}
+
+/// `\uXXXX`
+fn hex4(input: &[u8]) -> ParseResult<'_,u8,u32> {
+    let (rest, h) = right(starts_with(b"\\u"), expected(seq_exact(is_hex_digit, 4), "4 hex digits")).parse(input)?;
+    let h = h.iter().fold(0, |a, x| a*16 + (*x as char).to_digit(16).unwrap_or(0));
+    Ok((rest, h))
+}
@@ -0,0 +1,116 @@
+//! Lexer stage.
+//! `Lexer` turns `&[u8]` into `Vec<Token>` with parcelona rules, the parser then works over `&[Token]`
+//! with `tok` and `tok_text`. Tokens keep their offset, so errors map back to the source text.
+//!
+//! ```text
+//! let lexer = Lexer::new()
+//!     .skip(seq(is_space))
+//!     .rule(Kind::Num, seq(is_dec_digit))
+//!     .rule(Kind::Op, any(b"+-"));
+//! let tokens = lexer.tokenize(source).map_err(|e| e.into_owned(source))?;
+//! let r = sep_list(tok(Kind::Num), tok(Kind::Op), tok(Kind::Num)).parse_all(&tokens)
+//!     ... .map_err(|e| e.into_source(source))
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::dynamic::BoxParser;
+use crate::error::ParseError;
+use bstr::ByteSlice;
+use std::fmt;
+
+/// Token: kind, text and offset of the text in the source
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Token<'a,K> {
+    pub kind: K,
+    pub text: &'a [u8],
+    pub offset: usize,
+}
+
+/// rule of lexer, kind `None` is skipped text
+type Rule<'a,K> = (Option<K>, BoxParser<'a,u8,&'a [u8]>);
+
+/// Lexer builder, rules are tried all, the longest match wins, on a tie the earlier rule
+pub struct Lexer<'a,K> {
+    rules: Vec<Rule<'a,K>>,
+}
+
+impl<'a,K> Default for Lexer<'a,K> {
+    fn default() -> Self { Lexer { rules: Vec::new() } }
+}
+
+impl<'a,K:Copy> Lexer<'a,K> {
+    pub fn new() -> Self { Default::default() }
+
+    /// add token rule
+    pub fn rule<O:'a,P:Parser<'a,u8,O>+'a>(mut self, kind: K, p: P) -> Self {
+        self.rules.push((Some(kind), BoxParser::new(recognize(p))));
+        self
+    }
+
+    /// add rule of skipped text: spaces, comments
+    pub fn skip<O:'a,P:Parser<'a,u8,O>+'a>(mut self, p: P) -> Self {
+        self.rules.push((None, BoxParser::new(recognize(p))));
+        self
+    }
+
+    /// split source into tokens
+    pub fn tokenize(&self, source: &'a [u8]) -> Result<Vec<Token<'a,K>>, PErr<'a,u8>> {
+        let mut r = Vec::new();
+        let mut input = source;
+        while !input.is_empty() {
+            let mut best: Option<(Option<K>, &'a [u8], &'a [u8])> = None;
+            for (kind, p) in &self.rules {
+                if let Ok((rest, text)) = p.parse(input) {
+                    if !text.is_empty() && best.is_none_or(|x| text.len() > x.1.len()) {
+                        best = Some((*kind, text, rest));
+                    }
+                }
+            }
+            let Some((kind, text, rest)) = best else {
+                return Err(PErr::msg(input, "no token rule matches"));
+            };
+            if let Some(kind) = kind {
+                r.push(Token { kind, text, offset: source.len()-input.len() });
+            }
+            input = rest;
+        }
+        Ok(r)
+    }
+}
+
+/// parser one token of the kind
+pub fn tok<'a,K:'a+Copy+PartialEq>(kind: K) -> impl Parser<'a,Token<'a,K>,Token<'a,K>> {
+    move |input:&'a[Token<'a,K>]| {
+        match input.first() {
+            None                     => Err(PErr::new(input).set_eod()),
+            Some(t) if t.kind == kind => Ok((&input[1..], *t)),
+            Some(_)                  => Err(PErr::new(input)),
+        }
+    }
+}
+
+/// parser one token of the kind and text, e.g. keyword
+pub fn tok_text<'a,K:'a+Copy+PartialEq>(kind: K, text: &'a [u8]) -> impl Parser<'a,Token<'a,K>,Token<'a,K>> {
+    move |input:&'a[Token<'a,K>]| {
+        match input.first() {
+            None                                       => Err(PErr::new(input).set_eod()),
+            Some(t) if t.kind == kind && t.text == text => Ok((&input[1..], *t)),
+            Some(_)                                    => Err(PErr::new(input)),
+        }
+    }
+}
+
+impl<'a,K:fmt::Debug> Excerpt for Token<'a,K> {}
+
+impl<'a,K:'a+fmt::Debug> PErr<'a,Token<'a,K>> {
+    /// convert into owned error at the place of the source text, `source` is the text given to the lexer
+    pub fn into_source(self, source: &[u8]) -> ParseError {
+        let offset = self.get_input().first().map_or(source.len(), |t| t.offset).min(source.len());
+        let text = &source[offset..];
+        let mut r = ParseError::from(self);
+        r.offset = Some(offset);
+        r.remaining = text.len();
+        r.excerpt = format!("{:?}", text[..text.len().min(95)].as_bstr());
+        r
+    }
+}
@@ -4,3 +4,24 @@
 
 pub mod parser_combinators;
 pub mod u8;
+pub mod context;
+pub mod error;
+pub mod report;
+pub mod dynamic;
+pub mod bnf;
+pub mod packrat;
+pub mod lexer;
+pub mod json;
+pub mod csv;
+pub mod http;
+pub mod tls;
+pub mod dns;
+pub mod uri;
+pub mod net;
+pub mod datetime;
+pub mod config;
+pub mod resp;
+#[cfg(feature = "tokio")]
+pub mod codec;
+#[cfg(feature = "regex")]
+pub mod regex;
@@ -0,0 +1,177 @@
+//! Network addresses in text: IPv4, IPv6, CIDR prefixes and MAC addresses.
+//! IPv4 is dotted-quad with octets up to 255, leading zeros are an error as they read as octal elsewhere.
+//! IPv6 is any form of RFC 4291 2.2, e.g. compressed or with embedded IPv4, with optional zone ID.
+//! A parser stops after the address, e.g. `10.0.0.1:80` leaves `:80`.
+//!
+//! ```text
+//! let (rest, net) = cidr(b"192.168.0.0/16 allow")?;
+//! assert!(net.contains(&"192.168.1.1".parse()?));
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::u8::{is_dec_digit,is_hex_digit};
+use crate::uri::is_unreserved;
+use std::net::{IpAddr,Ipv4Addr,Ipv6Addr};
+use std::fmt;
+
+/// IP network, the address may have host bits
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Cidr {
+    pub addr: IpAddr,
+    /// a prefix above the address length means the whole address
+    pub prefix: u8,
+}
+
+impl Cidr {
+    /// address with host bits cleared
+    pub fn network(&self) -> IpAddr {
+        match self.addr {
+            IpAddr::V4(x) => IpAddr::V4((u32::from(x) & mask32(self.prefix)).into()),
+            IpAddr::V6(x) => IpAddr::V6((u128::from(x) & mask128(self.prefix)).into()),
+        }
+    }
+
+    /// address is in the network, addresses of other family are not
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(n), IpAddr::V4(x)) => (u32::from(n) ^ u32::from(*x)) & mask32(self.prefix) == 0,
+            (IpAddr::V6(n), IpAddr::V6(x)) => (u128::from(n) ^ u128::from(*x)) & mask128(self.prefix) == 0,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}/{}", self.addr, self.prefix) }
+}
+
+fn mask32(prefix: u8) -> u32 { u32::MAX.checked_shl(32u32.saturating_sub(u32::from(prefix))).unwrap_or(0) }
+fn mask128(prefix: u8) -> u128 { u128::MAX.checked_shl(128u32.saturating_sub(u32::from(prefix))).unwrap_or(0) }
+
+/// MAC address, EUI-48
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
+pub struct MacAddr(pub [u8; 6]);
+
+/// lower case, `:` separated
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+fn error<'a>(input: &'a [u8], what: &'static str) -> PErr<'a,u8> {
+    PErr::new(input).expected_push(Msg::Str(what)).fmt_str()
+}
+
+fn count(input: &[u8], p: fn(&u8) -> bool) -> usize { input.iter().take_while(|x| p(x)).count() }
+
+/// decimal number without leading zeros, up to `max`
+fn dec(input: &[u8], max: u32) -> Option<(&[u8], u32)> {
+    let n = count(input, is_dec_digit);
+    if n == 0 || n > 3 || n > 1 && input[0] == b'0' { return None; }
+    let x = input[..n].iter().fold(0, |a, c| a*10 + u32::from(c - b'0'));
+    (x <= max).then(|| (&input[n..], x))
+}
+
+/// parser of IPv4 address `d.d.d.d`
+pub fn ipv4(input: &[u8]) -> ParseResult<'_,u8,Ipv4Addr> {
+    let mut r = [0; 4];
+    let mut i = input;
+    for (k, x) in r.iter_mut().enumerate() {
+        if k > 0 { i = i.strip_prefix(b".").ok_or_else(|| error(input, "IPv4 address"))?; }
+        let (next, o) = dec(i, 255).ok_or_else(|| error(input, "IPv4 address"))?;
+        *x = o as u8;
+        i = next;
+    }
+    Ok((i, r.into()))
+}
+
+/// parser of IPv6 address without zone ID
+pub fn ipv6(input: &[u8]) -> ParseResult<'_,u8,Ipv6Addr> {
+    let bad = || error(input, "IPv6 address");
+    let mut head = Vec::with_capacity(8);
+    let mut tail = Vec::with_capacity(8);
+    let mut compressed = false;
+    let mut i = input;
+    if let Some(x) = i.strip_prefix(b"::") { compressed = true; i = x; }
+    loop {
+        let total = head.len() + tail.len();
+        let n = count(i, is_hex_digit);
+        let pieces = if compressed { &mut tail } else { &mut head };
+        if n == 0 && compressed && pieces.is_empty() { break; }
+        if i.get(n) == Some(&b'.') {
+            // embedded IPv4 takes the last two pieces
+            if total + 2 > 8 { return Err(bad()); }
+            let (next, v4) = ipv4(i).map_err(|_| bad())?;
+            let v4 = u32::from(v4);
+            pieces.extend([(v4 >> 16) as u16, v4 as u16]);
+            i = next;
+            break;
+        }
+        if n == 0 || n > 4 || total == 8 { return Err(bad()); }
+        pieces.push(i[..n].iter().fold(0, |a, c| a*16 + (*c as char).to_digit(16).unwrap_or(0) as u16));
+        i = &i[n..];
+        if total + 1 == 8 { break; }
+        if let Some(x) = i.strip_prefix(b"::") {
+            if compressed { return Err(bad()); }
+            compressed = true;
+            i = x;
+        } else if i.first() == Some(&b':') && i.get(1).is_some_and(is_hex_digit) {
+            i = &i[1..];
+        } else {
+            break;
+        }
+    }
+    let total = head.len() + tail.len();
+    if compressed && total > 7 || !compressed && total != 8 { return Err(bad()); }
+    let mut r = [0u16; 8];
+    r[..head.len()].copy_from_slice(&head);
+    r[8-tail.len()..].copy_from_slice(&tail);
+    Ok((i, r.into()))
+}
+
+/// parser of IPv6 address with optional zone ID `%zone`, RFC 4007
+pub fn ipv6_zone(input: &[u8]) -> ParseResult<'_,u8,(Ipv6Addr, Option<&[u8]>)> {
+    let (rest, a) = ipv6(input)?;
+    match rest.strip_prefix(b"%") {
+        Some(z) if z.first().is_some_and(is_unreserved) => {
+            let (rest, zone) = split_at_revers(z, count(z, is_unreserved));
+            Ok((rest, (a, Some(zone))))
+        },
+        _ => Ok((rest, (a, None))),
+    }
+}
+
+/// parser of IPv4 or IPv6 address
+pub fn ip(input: &[u8]) -> ParseResult<'_,u8,IpAddr> {
+    ipv4(input).map(|(rest, x)| (rest, IpAddr::V4(x)))
+        .or_else(|_| ipv6(input).map(|(rest, x)| (rest, IpAddr::V6(x))))
+        .map_err(|_| error(input, "IP address"))
+}
+
+/// parser of CIDR `address/prefix`
+pub fn cidr(input: &[u8]) -> ParseResult<'_,u8,Cidr> {
+    let (rest, addr) = ip(input)?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let (rest, prefix) = rest.strip_prefix(b"/").and_then(|x| dec(x, max))
+        .ok_or_else(|| error(rest, "`/` and prefix length"))?;
+    Ok((rest, Cidr { addr, prefix: prefix as u8 }))
+}
+
+/// parser of MAC address `01:23:45:67:89:ab`, `01-23-45-67-89-ab` or `0123.4567.89ab`
+pub fn mac(input: &[u8]) -> ParseResult<'_,u8,MacAddr> {
+    let hex = |x: &[u8]| x.iter().fold(0, |a, c| a*16 + (*c as char).to_digit(16).unwrap_or(0) as u8);
+    let mut r = [0; 6];
+    let dotted = input.len() >= 14 && input[4] == b'.' && input[9] == b'.';
+    let (sep, group) = if dotted { (b'.', 4) } else { (*input.get(2).unwrap_or(&b':'), 2) };
+    if !dotted && !matches!(sep, b':' | b'-') { return Err(error(input, "MAC address")); }
+    let mut i = input;
+    for k in 0..12/group {
+        if k > 0 { i = i.strip_prefix(&[sep]).ok_or_else(|| error(input, "MAC address"))?; }
+        if count(i, is_hex_digit) != group { return Err(error(input, "MAC address")); }
+        for (j, b) in i[..group].chunks(2).enumerate() { r[k*group/2 + j] = hex(b); }
+        i = &i[group..];
+    }
+    Ok((i, MacAddr(r)))
+}
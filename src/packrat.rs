@@ -0,0 +1,100 @@
+//! Packrat memoization.
+//! `memo(p, &table)` keeps results of `p` by input position, so `alt` backtracking
+//! does not parse the same rule at the same position twice.
+//! `lr(p, &table)` is a left recursive rule, e.g. `expr := expr "-" num / num`.
+//! A table lives as long as the input, keep tables of rules in a struct and pass it to rule functions:
+//!
+//! ```text
+//! struct Tables<'a> { term: MemoTable<'a,u8,i64> }
+//! fn term<'a,'t>(t:&'t Tables<'a>) -> impl Parser<'a,u8,i64>+'t { memo(move |i| ..., &t.term) }
+//! ```
+
+use crate::parser_combinators::{*};
+use std::{cell::RefCell,collections::HashMap};
+
+/// Cache of parser results, key is input position
+pub struct MemoTable<'a,I,O> {
+    cache: RefCell<HashMap<(usize,usize), ParseResult<'a,I,O>>>,
+}
+
+impl<'a,I,O> Default for MemoTable<'a,I,O> {
+    fn default() -> Self { MemoTable { cache: RefCell::new(HashMap::new()) } }
+}
+
+impl<'a,I:Clone,O:Clone> MemoTable<'a,I,O> {
+    pub fn new() -> Self { Default::default() }
+    /// count of cached results
+    pub fn len(&self) -> usize { self.cache.borrow().len() }
+    pub fn is_empty(&self) -> bool { self.cache.borrow().is_empty() }
+
+    fn get(&self, input:&'a [I]) -> Option<ParseResult<'a,I,O>> {
+        self.cache.borrow().get(&key(input)).cloned()
+    }
+
+    fn set(&self, input:&'a [I], r:&ParseResult<'a,I,O>) {
+        self.cache.borrow_mut().insert(key(input), r.clone());
+    }
+}
+
+/// clear cache of the table, for `memo_scope`
+pub trait MemoClear {
+    fn clear(&self);
+}
+
+impl<'a,I,O> MemoClear for MemoTable<'a,I,O> {
+    fn clear(&self) { self.cache.borrow_mut().clear(); }
+}
+
+/// input position: address and length of the rest of input
+#[inline]
+fn key<I>(input:&[I]) -> (usize,usize) { (input.as_ptr() as usize, input.len()) }
+
+/// combinator memo, results of parser are cached in the table by input position
+pub fn memo<'a,'t,I:'a+Clone,O:Clone,P>(p:P, table:&'t MemoTable<'a,I,O>) -> impl Parser<'a,I,O>+'t
+where
+    P: Parser<'a,I,O>+'t,
+{
+    move |input:&'a[I]| {
+        if let Some(r) = table.get(input) { return r; }
+        let r = p.parse(input);
+        table.set(input, &r);
+        r
+    }
+}
+
+/// combinator memo_scope, top-level parse: tables are cleared before and after the parse
+pub fn memo_scope<'a,'t,I:'a,O,P>(p:P, tables:&'t [&'t dyn MemoClear]) -> impl Parser<'a,I,O>+'t
+where
+    P: Parser<'a,I,O>+'t,
+{
+    move |input:&'a[I]| {
+        for t in tables { t.clear(); }
+        let r = p.parse(input);
+        for t in tables { t.clear(); }
+        r
+    }
+}
+
+/// combinator lr, left recursive rule, growing the seed:
+/// the rule is parsed again while it consumes more input, recursive calls get the previous result from the table.
+/// For indirect recursion only the head of the cycle is `lr`, other rules of the cycle must not be `memo`.
+pub fn lr<'a,'t,I:'a+Clone,O:Clone,P>(p:P, table:&'t MemoTable<'a,I,O>) -> impl Parser<'a,I,O>+'t
+where
+    P: Parser<'a,I,O>+'t,
+{
+    move |input:&'a[I]| {
+        if let Some(r) = table.get(input) { return r; }
+        table.set(input, &Err(PErr::new(input)));
+        let mut r = p.parse(input);
+        if r.is_err() { table.set(input, &r); return r; }
+        loop {
+            table.set(input, &r);
+            let next = p.parse(input);
+            match (&next, &r) {
+                (Ok((a,_)), Ok((b,_))) if a.len() < b.len() => r = next,
+                _ => break,
+            }
+        }
+        r
+    }
+}
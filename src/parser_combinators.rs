@@ -1,8 +1,11 @@
 //!  Parcelona minimalistic elegance parser combinator library.
 //!
-use parcelona_macros_derive::{alt_impl,permut_impl};
-use std::{fmt,mem,cmp,default,error};
+use parcelona_macros_derive::{alt_impl,permut_impl,seq_impl};
+pub use parcelona_macros_derive::grammar;
+use std::{fmt,cmp,default,error,marker::PhantomData};
 use bstr::ByteSlice;
+pub use crate::error::ParseError;
+use crate::dynamic::BoxParser;
 
 pub type ParseResult<'a,I,O> = std::result::Result<(&'a [I],O),PErr<'a,I>>;
 
@@ -13,11 +16,21 @@ pub enum Msg<'a> {
     String(String),
 }
 
+impl<'a> Msg<'a> {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Msg::Str(s)    => s,
+            Msg::String(s) => s,
+        }
+    }
+}
+
 /// type Error for parser
 #[derive(Debug,Clone)]
 pub struct PErr<'a,I> {
     input: &'a[I],
     user_msg: Vec<Msg<'a>>,
+    expected: Vec<Msg<'a>>,
     to_srt: bool,    
 /// set true if reason error parsing  it is end of data
     end_of_data:bool,          
@@ -25,42 +38,82 @@ pub struct PErr<'a,I> {
 
 impl<'a,I> default::Default for PErr<'a,I> {
     fn default() -> Self {
-        Self { input: &[], user_msg: Vec::new(), to_srt: false, end_of_data: false, }
+        Self { input: &[], user_msg: Vec::new(), expected: Vec::new(), to_srt: false, end_of_data: false, }
     }
 }
 
-impl<'a,I:'a+std::fmt::Debug> error::Error for PErr<'a,I> {}
+impl<'a,I:'a+Excerpt> error::Error for PErr<'a,I> {}
 
 impl<'a,I:'a> PErr<'a,I> {
     /// constructor of new PErr
     pub fn new(input: &'a[I]) -> Self {
-        Self { input: input, user_msg: Vec::<Msg>::new(), to_srt: false, end_of_data: false, }
+        Self { input, user_msg: Vec::<Msg>::new(), expected: Vec::<Msg>::new(), to_srt: false, end_of_data: false, }
     } 
+    /// error with a message, e.g. of malformed data, shown as str
+    pub fn msg(input: &'a[I], msg: &'a str) -> Self { Self::new(input).user_msg_push(Msg::Str(msg)).fmt_str() }
     /// set type to str for Display
     pub fn fmt_str(mut self) -> Self { self.to_srt=true; self }  
     pub fn user_msg_push(mut self, m: Msg<'a>) -> Self { self.user_msg.push(m); self }
+    /// add what was expected at the place of the error
+    pub fn expected_push(mut self, m: Msg<'a>) -> Self { self.expected.push(m); self }
     pub fn set_eod(mut self) -> Self { self.end_of_data=true; self }
     /// get last input from error
     pub fn get_input(&self) -> &'a[I] { self.input }
     /// set true if reason error parsing  it is end of data
     pub fn is_eod(&self) -> bool { self.end_of_data }
+    /// get user messages, in order of pushing
+    pub fn get_msg(&self) -> &[Msg<'a>] { &self.user_msg }
+    /// get expectations, in order of pushing
+    pub fn get_expected(&self) -> &[Msg<'a>] { &self.expected }
 }
 
-impl<'a,I:'a+fmt::Debug> fmt::Display for PErr<'a, I> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+/// element of input shown in errors, by Debug if not overridden,
+/// e.g. `impl Excerpt for MyToken {}`
+pub trait Excerpt: fmt::Debug + Sized {
+    /// `to_str` is set by `PErr::fmt_str`
+    fn excerpt(part: &[Self], to_str: bool) -> String {
+        let _ = to_str;
+        format!("{:?}", part)
+    }
+}
+
+/// bytes are shown as str if `to_str` is set
+impl Excerpt for u8 {
+    fn excerpt(part: &[u8], to_str: bool) -> String {
+        if to_str { format!("{:?}", part.as_bstr()) } else { format!("{:?}", part) }
+    }
+}
+
+impl Excerpt for char {}
+impl Excerpt for u16 {}
+impl Excerpt for u32 {}
+impl Excerpt for u64 {}
+impl Excerpt for i8 {}
+impl Excerpt for i16 {}
+impl Excerpt for i32 {}
+impl Excerpt for i64 {}
+impl<T:fmt::Debug> Excerpt for &T {}
+
+impl<'a,I:'a+Excerpt> PErr<'a,I> {
+    /// first 95 elements of the last input formatted as for Display
+    pub fn excerpt(&self) -> String {
         let max_ln:usize = 95;
         let part: &[I];
         if self.input.len()>max_ln { part = &self.input[..95]; }
         else { part = self.input; }
-        if self.to_srt {
-            unsafe {
-                let b = mem::transmute::<&[I], &[u8]>(part);
-                writeln!(f, "Err: {:?}", b.as_bstr())?;
-            }
-        } else { writeln!(f, "Err: {:?}", part)?; }
+        I::excerpt(part, self.to_srt)
+    }
+}
+
+impl<'a,I:'a+Excerpt> fmt::Display for PErr<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Err: {}", self.excerpt())?;
         for i in self.user_msg.iter().rev() {
             writeln!(f, "{:?}", i)?;
         }
+        for i in self.expected.iter() {
+            writeln!(f, "expected: {}", i.as_str())?;
+        }
         Ok(())
     }
 }
@@ -85,6 +138,23 @@ pub trait Parser<'a,I:'a,O>: Copy {
     fn not(self) -> impl Parser<'a,I,()>                              { not(self) }
     fn msg_err(self, msg:&'a str) -> impl Parser<'a,I,O>              { msg_err(self,msg) }
     fn strerr(self) -> impl Parser<'a,I,O>                            { strerr(self) }
+    fn expected(self, what:&'a str) -> impl Parser<'a,I,O>            { expected(self,what) }
+    fn boxed(self) -> BoxParser<'a,I,O> where Self:'a                 { BoxParser::new(self) }
+    fn iter(self, input:&'a [I]) -> ParseIter<'a,I,O,Self>            { parse_iter(self,input) }
+    /// parse and require the whole input to be consumed
+    fn parse_all(&self, input:&'a [I]) -> Result<O,ParseError> where I:Excerpt { self.parse_complete(input, |_|false) }
+    /// parse, skip trailing elements satisfying `skip` (e.g. `u8::is_space`)
+    /// and require the whole input to be consumed
+    fn parse_complete<F:Fn(&I)->bool>(&self, input:&'a [I], skip:F) -> Result<O,ParseError>
+    where I:Excerpt,
+    {
+        let (rest, r) = self.parse(input).map_err(|e| e.into_owned(input))?;
+        let n = rest.iter().take_while(|x| skip(x)).count();
+        if n<rest.len() {
+            return Err(PErr::new(&rest[n..]).user_msg_push(Msg::Str("unexpected trailing data")).into_owned(input));
+        }
+        Ok(r)
+    }
 }
 
 impl<'a,I:'a,F,O> Parser<'a,I,O> for F
@@ -226,11 +296,11 @@ impl<'a,I:'a+cmp::PartialEq+cmp::PartialOrd> Parser<'a,I,&'a[I]> for &StaticClas
         if new_input.is_empty() { break; }
         for i in self.parts_enable.0{ if new_input.starts_with(&i) { new_input = &new_input[i.len()..]; c+=i.len(); } }
         for i in self.parts_disable { if new_input.starts_with(&i) { break 'outer; } }
-        for i in self.range_enable  { if i.0<=new_input[0] && i.1>=new_input[0] { new_input = &new_input[1..]; c+=1; } }
-        for i in self.range_disable { if i.0<=new_input[0] && i.1>=new_input[0] { break 'outer; } }
-        if self.one_enable.contains(&new_input[0])  { new_input = &new_input[1..]; c+=1; } 
-        if self.one_disable.contains(&new_input[0]) { break 'outer; }  
-        if self.default_enable_one { new_input = &new_input[1..]; c+=1; }
+        for i in self.range_enable  { if !new_input.is_empty() && i.0<=new_input[0] && i.1>=new_input[0] { new_input = &new_input[1..]; c+=1; } }
+        for i in self.range_disable { if !new_input.is_empty() && i.0<=new_input[0] && i.1>=new_input[0] { break 'outer; } }
+        if !new_input.is_empty() && self.one_enable.contains(&new_input[0])  { new_input = &new_input[1..]; c+=1; } 
+        if !new_input.is_empty() && self.one_disable.contains(&new_input[0]) { break 'outer; }  
+        if !new_input.is_empty() && self.default_enable_one { new_input = &new_input[1..]; c+=1; }
         if inner_c==c { break; }
         inner_c = c;
     }
@@ -249,11 +319,11 @@ impl<'a,I:'a+cmp::PartialEq+cmp::PartialOrd> Parser<'a,I,&'a[I]> for &ClassOfSym
             if new_input.is_empty() { break; }
             for i in &self.parts_enable.0{ if new_input.starts_with(&i) { new_input = &new_input[i.len()..]; c+=i.len(); } }
             for i in &self.parts_disable { if new_input.starts_with(&i) { break 'outer; } }
-            for i in &self.range_enable  { if i.0<=new_input[0] && i.1>=new_input[0] { new_input = &new_input[1..]; c+=1; } }
-            for i in &self.range_disable { if i.0<=new_input[0] && i.1>=new_input[0] { break 'outer; } }
-            if self.one_enable.contains(&new_input[0])  { new_input = &new_input[1..]; c+=1; } 
-            if self.one_disable.contains(&new_input[0]) { break 'outer; }  
-            if self.default_enable_one { new_input = &new_input[1..]; c+=1; }
+            for i in &self.range_enable  { if !new_input.is_empty() && i.0<=new_input[0] && i.1>=new_input[0] { new_input = &new_input[1..]; c+=1; } }
+            for i in &self.range_disable { if !new_input.is_empty() && i.0<=new_input[0] && i.1>=new_input[0] { break 'outer; } }
+            if !new_input.is_empty() && self.one_enable.contains(&new_input[0])  { new_input = &new_input[1..]; c+=1; } 
+            if !new_input.is_empty() && self.one_disable.contains(&new_input[0]) { break 'outer; }  
+            if !new_input.is_empty() && self.default_enable_one { new_input = &new_input[1..]; c+=1; }
             if inner_c==c { break; }
             inner_c = c;
         }
@@ -332,6 +402,40 @@ permut_impl!(64);
 permut_impl!(16);  //max 255
 
 
+/// Seq trait combinator, parses a tuple of parsers in order and returns a flat tuple of results,
+/// it is implement for tuples default max 16 elements
+/// You can set cargo.toml flag `feature = "alt_tuple_32"` for up to tuple max 32 elements or `feature = "alt_tuple_64"` for up to tuple max 64 elements
+pub trait Seq<'a,I:'a,O>: Copy {
+    fn sequence(&self, input:&'a [I]) -> ParseResult<'a,I,O>;
+    /// (P1,P2,P3).tuple() -> impl Parser<'a,I,(O1,O2,O3)>
+    fn tuple(self) -> impl Parser<'a,I,O> { move |i| self.sequence(i) }
+}
+
+/// tuple combinator
+pub fn tuple<'a,I:'a,O,T:Seq<'a,I,O>>(input: T) -> impl Parser<'a,I,O> {
+    move |i| input.sequence(i)
+}
+
+/// seq_select combinator, parses a tuple of parsers in order and keeps only results with the given indexes
+/// `seq_select!((open, name, sep, value, close); 1, 3)` -> impl Parser<'a,I,(O1,O3)>,
+/// with one index the result is not a tuple
+#[macro_export]
+macro_rules! seq_select {
+    ($t:expr; $($i:tt),+) => {
+        $crate::parser_combinators::fmap($crate::parser_combinators::tuple($t), |r| ($(r.$i),+))
+    };
+}
+
+#[cfg(feature = "alt_tuple_32")]
+seq_impl!(32);  
+
+#[cfg(all(feature = "alt_tuple_64", not(feature = "alt_tuple_32")))] 
+seq_impl!(64);  
+
+#[cfg(not(any(feature = "alt_tuple_32", feature = "alt_tuple_64")))]
+seq_impl!(16);  //max 255
+
+
 /// parser `data end`
 pub fn data_end<'a,T>(a:&'a[T]) -> Result<(&[T],&[T]), PErr<'a,T>> {
     if !a.is_empty() { Err(PErr::new(a)) } else { Ok((a,a)) }
@@ -491,6 +595,28 @@ where
     }}
 }
 
+/// combinator peek, runs parser but does not consume input
+pub fn peek<'a,T:'a,P,R>(parser: P) -> impl Parser<'a,T,R>
+where
+    P: Parser<'a,T,R>,
+{
+    move |input| {
+        let (_, r) = parser.parse(input)?;
+        Ok((input, r))
+    }
+}
+
+/// combinator recognize, returns the part of input consumed by parser
+pub fn recognize<'a,T:'a,P,R>(parser: P) -> impl Parser<'a,T,&'a[T]>
+where
+    P: Parser<'a,T,R>,
+{
+    move |input:&'a[T]| {
+        let (next_input, _) = parser.parse(input)?;
+        Ok(split_at_revers(input, input.len()-next_input.len()))
+    }
+}
+
 /// combinator fmap
 pub fn fmap<'a,T:'a,F,P,R1,R2>(parser: P, map_fn: F) -> impl Parser<'a,T,R2>
 where
@@ -541,6 +667,14 @@ where
     move |input| { parser.parse(input).map_err(|mut x|{x.user_msg.push(Msg::Str(msg)); x}) }
 }
 
+/// combinator expected, on error adds what was expected at the place of the error
+pub fn expected<'a,T:'a,P,R>(parser: P, what: &'a str) -> impl Parser<'a,T,R>
+where
+    P: Parser<'a,T,R>,
+{
+    move |input| { parser.parse(input).map_err(|mut x|{x.expected.push(Msg::Str(what)); x}) }
+}
+
 /// combinator map_err
 pub fn strerr<'a,T:'a,P,R>(parser: P) -> impl Parser<'a,T,R>
 where
@@ -847,6 +981,101 @@ where
     fmap(or(p1,p2),f)
 }
 
+/// combinator flat_map, builds the next parser from the result of the previous one
+pub fn flat_map<'a,T:'a,P1,P2,F,R1,R2>(p:P1, f:F) -> impl Parser<'a,T,R2>
+where
+    P1: Parser<'a,T,R1>,
+    P2: Parser<'a,T,R2>,
+    F: Fn(R1) -> P2 + Copy,
+{
+    move |input| {
+        let (input, r) = p.parse(input)?;
+        f(r).parse(input)
+    }
+}
+
+/// combinator length_value, `inner` is applied to exactly `len` elements
+/// and must consume all of them
+pub fn length_value<'a,T:'a,Pl,P,R>(len:Pl, inner:P) -> impl Parser<'a,T,R>
+where
+    Pl: Parser<'a,T,usize>,
+    P: Parser<'a,T,R>,
+{
+    move |input| {
+        let (input, l) = len.parse(input)?;
+        let (rest, record) = take_record(input, l)?;
+        // errors point into the record, move them onto `input` so that offsets are of the whole source
+        let anchor = |mut e: PErr<'a,T>| { e.input = &input[l - e.input.len().min(l)..]; e };
+        let (tail, r) = inner.parse(record).map_err(anchor)?;
+        if !tail.is_empty() { return Err(anchor(PErr::new(tail))); }
+        Ok((rest, r))
+    }
+}
+
+/// combinator length_count, `item` is applied exactly `count` times
+pub fn length_count<'a,T:'a,Pc,P,R>(count:Pc, item:P) -> impl Parser<'a,T,Vec<R>>
+where
+    Pc: Parser<'a,T,usize>,
+    P: Parser<'a,T,R>,
+{
+    move |input| {
+        let (mut input, c) = count.parse(input)?;
+        let mut result = Vec::with_capacity(c.min(input.len()));
+        for _ in 0..c {
+            let (next_input, r) = item.parse(input)?;
+            result.push(r);
+            input = next_input;
+        }
+        Ok((input, result))
+    }
+}
+
+/// iterator of parser results, see `parse_iter`
+pub struct ParseIter<'a,I,O,P> {
+    parser: P,
+    input: &'a [I],
+    stalled: bool,
+    done: bool,
+    _out: PhantomData<fn() -> O>,
+}
+
+impl<'a,I,O,P> ParseIter<'a,I,O,P> {
+    /// input not parsed yet, after an error it is the input of the failed parse
+    pub fn remaining(&self) -> &'a [I] { self.input }
+}
+
+impl<'a,I:'a,O,P> Iterator for ParseIter<'a,I,O,P>
+where
+    P: Parser<'a,I,O>,
+{
+    type Item = Result<O,PErr<'a,I>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done { return None; }
+        if self.stalled { self.done = true; return Some(Err(PErr::msg(self.input, "parser made no progress"))); }
+        if self.input.is_empty() { return None; }
+        match self.parser.parse(self.input) {
+            Ok((next_input, r)) => {
+                // a parser that consumes nothing would repeat forever
+                self.stalled = next_input.len() == self.input.len();
+                self.input = next_input;
+                Some(Ok(r))
+            },
+            Err(e) => { self.done = true; Some(Err(e)) },
+        }
+    }
+}
+
+/// lazy `more`: applies the parser repeatedly, stops after the first error
+/// or at the end of data, a result that consumes nothing is followed by
+/// the error "parser made no progress"
+pub fn parse_iter<'a,I:'a,O,P>(parser:P, input:&'a [I]) -> ParseIter<'a,I,O,P>
+where
+    P: Parser<'a,I,O>,
+{
+    ParseIter { parser, input, stalled: false, done: false, _out: PhantomData }
+}
+
 /// combinator separated list
 ///1) h, h, h, h hh
 ///   ----------
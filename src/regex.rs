@@ -0,0 +1,62 @@
+//! Regex parser, feature `regex`.
+//! The pattern is anchored at the start of the input, `&Re` is a `Parser` as `&ClassOfSymbols` is.
+//!
+//! ```text
+//! let uuid = regex(r"[0-9a-f]{8}-([0-9a-f]{4}-){3}[0-9a-f]{12}").unwrap();
+//! let (input, id) = right(starts_with(b"id="), &uuid).parse(input)?;
+//! ```
+
+use crate::parser_combinators::{*};
+use ::regex::bytes::Regex;
+
+/// Regex parser, returns the matched slice
+#[derive(Debug,Clone)]
+pub struct Re {
+    re: Regex,
+    pattern: String,
+}
+
+/// constructor of regex parser
+pub fn regex(pattern: &str) -> Result<Re, ::regex::Error> { Re::new(pattern) }
+
+impl Re {
+    pub fn new(pattern: &str) -> Result<Self, ::regex::Error> {
+        Ok(Re { re: Regex::new(&format!(r"\A(?:{})", pattern))?, pattern: pattern.to_string() })
+    }
+
+    /// pattern as given to constructor
+    pub fn as_str(&self) -> &str { &self.pattern }
+
+    /// parser returns capture groups, index 0 is the whole match
+    pub fn captures(&self) -> ReCaptures<'_> { ReCaptures(self) }
+
+    fn err<'a>(&self, input: &'a [u8]) -> PErr<'a,u8> {
+        let e = PErr::new(input).expected_push(Msg::String(format!("/{}/", self.pattern))).fmt_str();
+        if input.is_empty() { e.set_eod() } else { e }
+    }
+}
+
+impl<'a> Parser<'a,u8,&'a [u8]> for &Re {
+    fn parse(&self, input: &'a [u8]) -> ParseResult<'a,u8,&'a [u8]> {
+        match self.re.find(input) {
+            Some(m) => Ok(split_at_revers(input, m.end())),
+            None    => Err(self.err(input)),
+        }
+    }
+}
+
+/// Regex parser of capture groups, see `Re::captures`
+#[derive(Debug,Clone,Copy)]
+pub struct ReCaptures<'r>(&'r Re);
+
+impl<'a,'r> Parser<'a,u8,Vec<Option<&'a [u8]>>> for ReCaptures<'r> {
+    fn parse(&self, input: &'a [u8]) -> ParseResult<'a,u8,Vec<Option<&'a [u8]>>> {
+        match self.0.re.captures(input) {
+            Some(c) => {
+                let r = c.iter().map(|x| x.map(|m| m.as_bytes())).collect();
+                Ok((&input[c.get(0).map_or(0, |m| m.end())..], r))
+            },
+            None => Err(self.0.err(input)),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Diagnostic rendering.
+//! Renders a `ParseError` against the full source as a multi-line report
+//! with line number, the failing line, underline, message chain and expectations.
+//!
+//! ```text
+//! error: line parse error
+//!  --> conf.ini:2:5
+//!   |
+//! 2 | b = x2
+//!   |     ^^
+//!   = note: value parse error
+//!   = expected: number
+//! ```
+
+use crate::error::ParseError;
+use std::fmt::Write;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Report renderer
+#[derive(Debug,Clone,Copy)]
+pub struct Report<'s> {
+    source: &'s [u8],
+    name: Option<&'s str>,
+    color: bool,
+}
+
+impl<'s> Report<'s> {
+    /// `source` is the whole input of the parse
+    pub fn new(source: &'s [u8]) -> Self {
+        Report { source, name: None, color: false }
+    }
+
+    /// file name shown in the location line
+    pub fn name(mut self, name: &'s str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// use ANSI colors
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// line and column (both from 1) of the offset
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.source.len());
+        let before = &self.source[..offset];
+        let line = before.iter().filter(|x| **x == b'\n').count() + 1;
+        let start = before.iter().rposition(|x| *x == b'\n').map_or(0, |x| x + 1);
+        (line, offset - start + 1)
+    }
+
+    /// render error
+    pub fn render(&self, err: &ParseError) -> String {
+        let offset = err.offset()
+            .unwrap_or(self.source.len().saturating_sub(err.remaining()))
+            .min(self.source.len());
+        let (line, col) = self.line_col(offset);
+        let start = offset + 1 - col;
+        let end = self.source[offset..].iter().position(|x| *x == b'\n').map_or(self.source.len(), |x| offset + x);
+        let mut text = &self.source[start..end];
+        if let [rest @ .., b'\r'] = text { text = rest; }
+        let prefix = expand_tabs(&self.source[start..offset]);
+        let text = expand_tabs(text);
+
+        let mut mark = self.source[offset..end].iter().take_while(|x| !x.is_ascii_whitespace()).count();
+        if mark == 0 || err.is_eod() { mark = 1; }
+
+        let (red, blue, bold, reset) = if self.color { (RED, BLUE, BOLD, RESET) } else { ("", "", "", "") };
+        let gutter = " ".repeat(line.to_string().len());
+        let mut msg = err.get_msg().iter().rev();
+        let head = msg.next().map_or("parse error", String::as_str);
+        let mut r = String::new();
+
+        _ = writeln!(r, "{}error{}: {}{}{}", red, reset, bold, head, reset);
+        match self.name {
+            Some(name) => _ = writeln!(r, "{}{}-->{} {}:{}:{}", gutter, blue, reset, name, line, col),
+            None       => _ = writeln!(r, "{}{}-->{} {}:{}", gutter, blue, reset, line, col),
+        }
+        _ = writeln!(r, "{} {}|{}", gutter, blue, reset);
+        _ = writeln!(r, "{}{} |{} {}", blue, line, reset, text);
+        _ = writeln!(r, "{} {}|{} {}{}{}{}", gutter, blue, reset,
+            " ".repeat(prefix.chars().count()), red, "^".repeat(mark), reset);
+        if err.is_eod() {
+            _ = writeln!(r, "{} {}={} note: unexpected end of data", gutter, blue, reset);
+        }
+        for i in msg {
+            _ = writeln!(r, "{} {}={} note: {}", gutter, blue, reset, i);
+        }
+        if !err.get_expected().is_empty() {
+            _ = writeln!(r, "{} {}={} expected: {}", gutter, blue, reset, err.get_expected().join(", "));
+        }
+        r
+    }
+}
+
+fn expand_tabs(b: &[u8]) -> String {
+    String::from_utf8_lossy(b).replace('\t', "    ")
+}
@@ -0,0 +1,288 @@
+//! Redis serialization protocol, RESP2 and RESP3.
+//! All types: simple strings and errors, integers, bulk strings and errors, arrays, null,
+//! booleans, doubles, big numbers, verbatim strings, maps, sets, push, attributes
+//! and streamed strings and aggregates. Strings borrow the input.
+//! Incomplete input is an error with `is_eod()`, `pipeline` reads replies or commands
+//! one after another and keeps the incomplete tail for the next read.
+//!
+//! ```text
+//! let mut p = resp::pipeline(Limits::default(), buf);
+//! for v in &mut p { forward(v?); }
+//! let keep = p.remaining().len();
+//! ```
+
+use crate::parser_combinators::{*};
+use std::borrow::Cow;
+
+/// RESP value
+#[derive(Debug,Clone,PartialEq)]
+pub enum Value<'a> {
+    SimpleString(&'a [u8]),
+    /// simple error
+    Error(&'a [u8]),
+    Integer(i64),
+    /// owned if streamed
+    BulkString(Cow<'a,[u8]>),
+    /// RESP3 null, RESP2 null bulk string and null array
+    Null,
+    Array(Vec<Value<'a>>),
+    Boolean(bool),
+    Double(f64),
+    /// decimal digits with optional sign
+    BigNumber(&'a [u8]),
+    BulkError(&'a [u8]),
+    /// `format` is 3 chars, e.g. `txt` or `mkd`
+    Verbatim { format: &'a [u8], text: &'a [u8] },
+    Map(Vec<(Value<'a>, Value<'a>)>),
+    Set(Vec<Value<'a>>),
+    Push(Vec<Value<'a>>),
+    /// attributes of the value that follows them
+    Attribute { attrs: Vec<(Value<'a>, Value<'a>)>, value: Box<Value<'a>> },
+}
+
+impl<'a> Value<'a> {
+    /// simple, bulk or verbatim string
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::SimpleString(x) | Value::Verbatim { text: x, .. } => Some(x),
+            Value::BulkString(x) => Some(x),
+            _ => None,
+        }
+    }
+    pub fn as_i64(&self) -> Option<i64> { if let Value::Integer(x) = self { Some(*x) } else { None } }
+    /// array, set or push
+    pub fn as_array(&self) -> Option<&[Value<'a>]> {
+        match self { Value::Array(x) | Value::Set(x) | Value::Push(x) => Some(x), _ => None }
+    }
+    pub fn is_null(&self) -> bool { matches!(self, Value::Null) }
+    /// simple or bulk error
+    pub fn is_error(&self) -> bool { matches!(self, Value::Error(_) | Value::BulkError(_)) }
+}
+
+/// limits of a value
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Limits {
+    max_bulk_len: usize,
+    max_line_len: usize,
+    max_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self { Limits { max_bulk_len: 512*1024*1024, max_line_len: 64*1024, max_depth: 128 } }
+}
+
+impl Limits {
+    /// max length of bulk string, bulk error, verbatim string and whole streamed string
+    pub fn max_bulk_len(mut self, n: usize) -> Self {
+        self.max_bulk_len = n;
+        self
+    }
+
+    /// max length of simple string, simple error and other line types
+    pub fn max_line_len(mut self, n: usize) -> Self {
+        self.max_line_len = n;
+        self
+    }
+
+    /// max nesting of aggregates
+    pub fn max_depth(mut self, n: usize) -> Self {
+        self.max_depth = n;
+        self
+    }
+}
+
+/// line without CRLF
+fn line(input: &[u8], limits: Limits) -> ParseResult<'_,u8,&[u8]> {
+    let n = input.iter().take(limits.max_line_len + 1).position(|x| *x == b'\r' || *x == b'\n');
+    match n {
+        Some(n) if input[n] == b'\r' && input.get(n+1) == Some(&b'\n') => Ok((&input[n+2..], &input[..n])),
+        Some(n) if input[n] == b'\r' && n+1 == input.len() => Err(PErr::new(input).set_eod()),
+        Some(n) => Err(PErr::new(&input[n..]).expected_push(Msg::Str("CRLF")).fmt_str()),
+        None if input.len() > limits.max_line_len => Err(PErr::msg(input, "line is too long")),
+        None => Err(PErr::new(input).set_eod()),
+    }
+}
+
+/// decimal integer with optional sign
+fn int(s: &[u8]) -> Option<i64> {
+    let digits = s.strip_prefix(b"-").or_else(|| s.strip_prefix(b"+")).unwrap_or(s);
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) { return None; }
+    std::str::from_utf8(s).ok()?.parse().ok()
+}
+
+/// length of bulk or aggregate, `None` is `-1`
+fn length<'a>(input: &'a [u8], s: &[u8], max: usize) -> Result<Option<usize>, PErr<'a,u8>> {
+    match int(s) {
+        Some(-1) => Ok(None),
+        Some(x) if x >= 0 && x as u64 <= max as u64 => Ok(Some(x as usize)),
+        Some(_)  => Err(PErr::msg(input, "length is out of range")),
+        None     => Err(PErr::new(input).expected_push(Msg::Str("length")).fmt_str()),
+    }
+}
+
+/// `len` bytes and CRLF
+fn bulk(input: &[u8], len: usize) -> ParseResult<'_,u8,&[u8]> {
+    let (rest, data) = take_record(input, len)?;
+    match rest {
+        [b'\r', b'\n', rest @ ..] => Ok((rest, data)),
+        [] | [b'\r']              => Err(PErr::new(rest).set_eod()),
+        _                         => Err(PErr::new(rest).expected_push(Msg::Str("CRLF")).fmt_str()),
+    }
+}
+
+/// parser of value
+pub fn value<'a>(limits: Limits) -> impl Parser<'a,u8,Value<'a>> {
+    move |input: &'a [u8]| { parse_value(input, limits, limits.max_depth) }
+}
+
+/// parser of value, returns the raw bytes of it, e.g. to forward
+pub fn frame<'a>(limits: Limits) -> impl Parser<'a,u8,&'a [u8]> {
+    recognize(value(limits))
+}
+
+fn parse_value(input: &[u8], limits: Limits, depth: usize) -> ParseResult<'_,u8,Value<'_>> {
+    let Some((t, body)) = input.split_first() else { return Err(PErr::new(input).set_eod()) };
+    let (rest, l) = line(body, limits)?;
+    let v = match t {
+        b'+' => Value::SimpleString(l),
+        b'-' => Value::Error(l),
+        b':' => Value::Integer(int(l).ok_or_else(|| PErr::new(body).expected_push(Msg::Str("integer")).fmt_str())?),
+        b'_' if l.is_empty() => Value::Null,
+        b'#' => match l {
+            b"t" => Value::Boolean(true),
+            b"f" => Value::Boolean(false),
+            _    => return Err(PErr::new(body).expected_push(Msg::Str("`t` or `f`")).fmt_str()),
+        },
+        b',' => {
+            // `inf`, `-inf` and `nan` too
+            let x = std::str::from_utf8(l).ok().and_then(|x| x.parse().ok());
+            Value::Double(x.ok_or_else(|| PErr::new(body).expected_push(Msg::Str("double")).fmt_str())?)
+        },
+        b'(' => {
+            let digits = l.strip_prefix(b"-").or_else(|| l.strip_prefix(b"+")).unwrap_or(l);
+            if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+                return Err(PErr::new(body).expected_push(Msg::Str("big number")).fmt_str());
+            }
+            Value::BigNumber(l)
+        },
+        b'$' if l == b"?" => return streamed_string(rest, limits),
+        b'$' | b'!' | b'=' => {
+            let Some(n) = length(body, l, limits.max_bulk_len)? else {
+                if *t == b'$' { return Ok((rest, Value::Null)); }
+                return Err(PErr::msg(body, "length is out of range"));
+            };
+            let (rest, data) = bulk(rest, n)?;
+            let v = match t {
+                b'$' => Value::BulkString(Cow::Borrowed(data)),
+                b'!' => Value::BulkError(data),
+                _ => match data {
+                    [_, _, _, b':', text @ ..] => Value::Verbatim { format: &data[..3], text },
+                    _ => return Err(PErr::msg(body, "verbatim string without format")),
+                },
+            };
+            return Ok((rest, v));
+        },
+        b'*' | b'~' | b'>' | b'%' | b'|' => {
+            if depth == 0 { return Err(PErr::msg(input, "nesting is too deep")); }
+            let pairs = matches!(t, b'%' | b'|');
+            let n = if l == b"?" && *t != b'>' && *t != b'|' { None } else {
+                match length(body, l, usize::MAX)? {
+                    Some(n) => Some(n),
+                    None if *t == b'*' => return Ok((rest, Value::Null)),
+                    None => return Err(PErr::msg(body, "length is out of range")),
+                }
+            };
+            let (rest, items) = elements(rest, limits, depth-1, n.map(|x| if pairs { x.saturating_mul(2) } else { x }))?;
+            if pairs && items.len() % 2 != 0 { return Err(PErr::msg(rest, "map without a value of the last key")); }
+            let v = match t {
+                b'*' => Value::Array(items),
+                b'~' => Value::Set(items),
+                b'>' => Value::Push(items),
+                b'%' => Value::Map(into_pairs(items)),
+                _ => {
+                    let (rest, v) = parse_value(rest, limits, depth-1)?;
+                    return Ok((rest, Value::Attribute { attrs: into_pairs(items), value: Box::new(v) }));
+                },
+            };
+            return Ok((rest, v));
+        },
+        _ => return Err(PErr::new(input).expected_push(Msg::Str("type")).fmt_str()),
+    };
+    Ok((rest, v))
+}
+
+fn into_pairs(items: Vec<Value<'_>>) -> Vec<(Value<'_>, Value<'_>)> {
+    let mut r = Vec::with_capacity(items.len()/2);
+    let mut it = items.into_iter();
+    while let (Some(k), Some(v)) = (it.next(), it.next()) { r.push((k, v)); }
+    r
+}
+
+/// `n` values, streamed aggregate ends with `.` if `n` is `None`
+fn elements(input: &[u8], limits: Limits, depth: usize, n: Option<usize>) -> ParseResult<'_,u8,Vec<Value<'_>>> {
+    let mut r = Vec::new();
+    let mut i = input;
+    loop {
+        match n {
+            Some(n) if r.len() == n => return Ok((i, r)),
+            None => if let Some(x) = i.strip_prefix(b".") {
+                let (rest, l) = line(x, limits)?;
+                if !l.is_empty() { return Err(PErr::new(x).expected_push(Msg::Str("CRLF")).fmt_str()); }
+                return Ok((rest, r));
+            },
+            _ => {},
+        }
+        let (next, v) = parse_value(i, limits, depth)?;
+        r.push(v);
+        i = next;
+    }
+}
+
+/// chunks `;len CRLF data CRLF` up to `;0 CRLF`
+fn streamed_string(input: &[u8], limits: Limits) -> ParseResult<'_,u8,Value<'_>> {
+    let mut r = Vec::new();
+    let mut i = input;
+    loop {
+        let Some(body) = i.strip_prefix(b";") else {
+            if i.is_empty() { return Err(PErr::new(i).set_eod()); }
+            return Err(PErr::new(i).expected_push(Msg::Str("`;`")).fmt_str());
+        };
+        let (rest, l) = line(body, limits)?;
+        let n = length(body, l, limits.max_bulk_len)?.ok_or_else(|| PErr::msg(body, "length is out of range"))?;
+        if n == 0 { return Ok((rest, Value::BulkString(Cow::Owned(r)))); }
+        if n > limits.max_bulk_len - r.len() { return Err(PErr::msg(body, "length is out of range")); }
+        let (rest, data) = bulk(rest, n)?;
+        r.extend_from_slice(data);
+        i = rest;
+    }
+}
+
+/// iterator of pipelined values, stops at incomplete value or after the first error
+pub struct Pipeline<'a> {
+    limits: Limits,
+    input: &'a [u8],
+    error: bool,
+}
+
+/// values of the input one after another
+pub fn pipeline(limits: Limits, input: &[u8]) -> Pipeline<'_> {
+    Pipeline { limits, input, error: false }
+}
+
+impl<'a> Pipeline<'a> {
+    /// input not read yet, the incomplete value at the end
+    pub fn remaining(&self) -> &'a [u8] { self.input }
+}
+
+impl<'a> Iterator for Pipeline<'a> {
+    type Item = Result<Value<'a>,PErr<'a,u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error || self.input.is_empty() { return None; }
+        match parse_value(self.input, self.limits, self.limits.max_depth) {
+            Ok((input, v))         => { self.input = input; Some(Ok(v)) },
+            Err(e) if e.is_eod()   => None,
+            Err(e)                 => { self.error = true; Some(Err(e)) },
+        }
+    }
+}
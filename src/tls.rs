@@ -0,0 +1,273 @@
+//! TLS record layer and hello messages, RFC 8446.
+//! Incomplete record is an error with `is_eod()`, so a streaming proxy waits for more data.
+//! A complete record with bad content is a plain error: the data will not grow.
+//! Unknown and GREASE (RFC 8701) values are kept, helpers skip GREASE.
+//!
+//! ```text
+//! let (rest, hello) = client_hello_record(input)?;
+//! let host = hello.sni();
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::u8::{*};
+use std::borrow::Cow;
+
+pub const CHANGE_CIPHER_SPEC: u8 = 20;
+pub const ALERT: u8 = 21;
+pub const HANDSHAKE: u8 = 22;
+pub const APPLICATION_DATA: u8 = 23;
+
+pub const CLIENT_HELLO: u8 = 1;
+pub const SERVER_HELLO: u8 = 2;
+
+pub const EXT_SERVER_NAME: u16 = 0;
+pub const EXT_ALPN: u16 = 16;
+pub const EXT_SUPPORTED_VERSIONS: u16 = 43;
+pub const EXT_KEY_SHARE: u16 = 51;
+
+/// max length of record fragment, ciphertext
+pub const MAX_RECORD_LEN: usize = (1<<14) + 2048;
+
+/// random of ServerHello that is HelloRetryRequest
+pub const HELLO_RETRY_REQUEST: [u8; 32] = [
+    0xCF, 0x21, 0xAD, 0x74, 0xE5, 0x9A, 0x61, 0x11, 0xBE, 0x1D, 0x8C, 0x02, 0x1E, 0x65, 0xB8, 0x91,
+    0xC2, 0xA2, 0x11, 0x16, 0x7A, 0xBB, 0x8C, 0x5E, 0x07, 0x9E, 0x09, 0xE2, 0xC8, 0xA8, 0x33, 0x9C,
+];
+
+/// GREASE value, `0x?A?A`
+pub fn is_grease(x: u16) -> bool { x & 0x0f0f == 0x0a0a && x >> 8 == x & 0xff }
+
+/// record of record layer
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Record<'a> {
+    pub content_type: u8,
+    pub version: u16,
+    pub fragment: &'a [u8],
+}
+
+/// handshake message
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Handshake<'a> {
+    pub msg_type: u8,
+    pub body: &'a [u8],
+}
+
+/// key share entry, `key_exchange` is empty in HelloRetryRequest
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct KeyShare<'a> {
+    pub group: u16,
+    pub key_exchange: &'a [u8],
+}
+
+/// hello extension, in ServerHello lists have one element
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Extension<'a> {
+    /// host names, empty in ServerHello
+    ServerName(Vec<&'a [u8]>),
+    Alpn(Vec<&'a [u8]>),
+    SupportedVersions(Vec<u16>),
+    KeyShare(Vec<KeyShare<'a>>),
+    Unknown { typ: u16, data: &'a [u8] },
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct ClientHello<'a> {
+    pub legacy_version: u16,
+    pub random: &'a [u8],
+    pub session_id: &'a [u8],
+    pub cipher_suites: Vec<u16>,
+    pub compression_methods: &'a [u8],
+    pub extensions: Vec<Extension<'a>>,
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct ServerHello<'a> {
+    pub legacy_version: u16,
+    pub random: &'a [u8],
+    pub session_id: &'a [u8],
+    pub cipher_suite: u16,
+    pub compression_method: u8,
+    pub extensions: Vec<Extension<'a>>,
+}
+
+impl<'a> ClientHello<'a> {
+    /// first host name of server_name
+    pub fn sni(&self) -> Option<&'a [u8]> {
+        self.extensions.iter().find_map(|x| if let Extension::ServerName(v) = x { v.first().copied() } else { None })
+    }
+
+    /// protocols of ALPN
+    pub fn alpn(&self) -> Option<&[&'a [u8]]> {
+        self.extensions.iter().find_map(|x| if let Extension::Alpn(v) = x { Some(v.as_slice()) } else { None })
+    }
+
+    /// supported_versions without GREASE
+    pub fn supported_versions(&self) -> Vec<u16> {
+        self.extensions.iter()
+            .find_map(|x| if let Extension::SupportedVersions(v) = x { Some(v.as_slice()) } else { None })
+            .unwrap_or_default()
+            .iter().copied().filter(|x| !is_grease(*x)).collect()
+    }
+
+    /// key shares without GREASE
+    pub fn key_shares(&self) -> Vec<KeyShare<'a>> {
+        self.extensions.iter()
+            .find_map(|x| if let Extension::KeyShare(v) = x { Some(v.as_slice()) } else { None })
+            .unwrap_or_default()
+            .iter().copied().filter(|x| !is_grease(x.group)).collect()
+    }
+
+    /// cipher suites without GREASE
+    pub fn cipher_suites(&self) -> Vec<u16> {
+        self.cipher_suites.iter().copied().filter(|x| !is_grease(*x)).collect()
+    }
+}
+
+impl<'a> ServerHello<'a> {
+    pub fn is_hello_retry_request(&self) -> bool { self.random == HELLO_RETRY_REQUEST }
+
+    /// negotiated version: supported_versions or legacy_version
+    pub fn version(&self) -> u16 {
+        self.extensions.iter()
+            .find_map(|x| if let Extension::SupportedVersions(v) = x { v.first().copied() } else { None })
+            .unwrap_or(self.legacy_version)
+    }
+}
+
+/// parser big endian u8
+fn be_u8(input: &[u8]) -> ParseResult<'_,u8,u8> {
+    fmap(take_len_be_u8, |x| x as u8).parse(input)
+}
+
+/// adds `what` to the error, truncated data stays `is_eod()`
+fn malformed<'a>(what: &'static str) -> impl Fn(PErr<'a,u8>) -> PErr<'a,u8> {
+    move |e| e.user_msg_push(Msg::Str(what))
+}
+
+/// error in a complete record is not end of data, messages and expectations are kept
+fn in_complete_record(e: PErr<'_,u8>) -> PErr<'_,u8> {
+    let mut r = PErr::new(e.get_input());
+    for m in e.get_msg() { r = r.user_msg_push(m.clone()); }
+    for m in e.get_expected() { r = r.expected_push(m.clone()); }
+    r
+}
+
+/// parser of record, incomplete record is `is_eod()`
+pub fn record(input: &[u8]) -> ParseResult<'_,u8,Record<'_>> {
+    let (rest, (content_type, version, len)) = tuple((be_u8, be_u16, take_len_be_u16)).parse(input)?;
+    if !(CHANGE_CIPHER_SPEC..=APPLICATION_DATA).contains(&content_type) {
+        return Err(PErr::new(input).user_msg_push(Msg::Str("unknown tls content type")));
+    }
+    if len > MAX_RECORD_LEN {
+        return Err(PErr::new(input).user_msg_push(Msg::Str("tls record overflow")));
+    }
+    let (rest, fragment) = take_record(rest, len)?;
+    Ok((rest, Record { content_type, version, fragment }))
+}
+
+/// parser of handshake message: type and u24 length
+pub fn handshake(input: &[u8]) -> ParseResult<'_,u8,Handshake<'_>> {
+    let (rest, (msg_type, body)) = pair(be_u8, take_record_be_u24).parse(input)?;
+    Ok((rest, Handshake { msg_type, body }))
+}
+
+/// parser of handshake message spread over handshake records, the result is the message with its header.
+/// Borrows the input if the message is in one record. Next messages in the last record are not kept.
+pub fn handshake_reassemble(input: &[u8]) -> ParseResult<'_,u8,Cow<'_,[u8]>> {
+    let (mut rest, r) = record(input)?;
+    if r.content_type != HANDSHAKE { return Err(PErr::new(input).user_msg_push(Msg::Str("not a handshake record"))); }
+    let need = match tuple((be_u8, take_len_be_u24)).parse(r.fragment) {
+        Ok((_, (_, len))) => len + 4,
+        Err(_)            => 4,
+    };
+    if r.fragment.len() >= need { return Ok((rest, Cow::Borrowed(&r.fragment[..need]))); }
+    let mut msg = r.fragment.to_vec();
+    let mut need = need;
+    while msg.len() < need {
+        let (next_rest, r) = record(rest)?;
+        if r.content_type != HANDSHAKE { return Err(PErr::new(rest).user_msg_push(Msg::Str("not a handshake record"))); }
+        msg.extend_from_slice(r.fragment);
+        if need == 4 && msg.len() >= 4 { need = 4 + ((msg[1] as usize) << 16 | (msg[2] as usize) << 8 | msg[3] as usize); }
+        rest = next_rest;
+    }
+    msg.truncate(need);
+    Ok((rest, Cow::Owned(msg)))
+}
+
+fn server_names(input: &[u8]) -> ParseResult<'_,u8,Vec<&[u8]>> {
+    let name = fmap(pair(be_u8, take_record_be_u16), |(_, x)| x);
+    length_value(take_len_be_u16, more_min(name, 0)).parse(input)
+}
+
+fn alpn(input: &[u8]) -> ParseResult<'_,u8,Vec<&[u8]>> {
+    length_value(take_len_be_u16, more_min(take_record_be_u8, 0)).parse(input)
+}
+
+fn key_share(input: &[u8]) -> ParseResult<'_,u8,KeyShare<'_>> {
+    fmap(pair(be_u16, take_record_be_u16), |(group, key_exchange)| KeyShare { group, key_exchange }).parse(input)
+}
+
+/// result of parser that must consume all the data
+fn all<'a,O>(r: ParseResult<'a,u8,O>) -> Result<O,PErr<'a,u8>> {
+    match r {
+        Ok(([], x))                      => Ok(x),
+        Ok((rest, _))                    => Err(PErr::new(rest)),
+        Err(e)                           => Err(e),
+    }
+}
+
+/// extension data by type, `client` is for ClientHello
+fn extension_data(typ: u16, data: &[u8], client: bool) -> Result<Extension<'_>, PErr<'_,u8>> {
+    match (typ, client) {
+        (EXT_SERVER_NAME, _) if data.is_empty() => Ok(Extension::ServerName(Vec::new())),
+        (EXT_SERVER_NAME, _)       => all(fmap(server_names, Extension::ServerName).parse(data)),
+        (EXT_ALPN, _)              => all(fmap(alpn, Extension::Alpn).parse(data)),
+        (EXT_SUPPORTED_VERSIONS, true)  => all(fmap(length_value(take_len_be_u8, more_min(be_u16, 0)), Extension::SupportedVersions).parse(data)),
+        (EXT_SUPPORTED_VERSIONS, false) => all(fmap(be_u16, |x| Extension::SupportedVersions(vec![x])).parse(data)),
+        (EXT_KEY_SHARE, true)      => all(fmap(length_value(take_len_be_u16, more_min(key_share, 0)), Extension::KeyShare).parse(data)),
+        // HelloRetryRequest has only the group
+        (EXT_KEY_SHARE, false) if data.len() == 2 => all(fmap(be_u16, |group| Extension::KeyShare(vec![KeyShare { group, key_exchange: &[] }])).parse(data)),
+        (EXT_KEY_SHARE, false)     => all(fmap(key_share, |x| Extension::KeyShare(vec![x])).parse(data)),
+        _                          => Ok(Extension::Unknown { typ, data }),
+    }
+}
+
+fn extensions(input: &[u8], client: bool) -> ParseResult<'_,u8,Vec<Extension<'_>>> {
+    if input.is_empty() { return Ok((input, Vec::new())); }
+    let (rest, list) = take_record_be_u16(input)?;
+    let raw = more_min(pair(be_u16, take_record_be_u16), 0);
+    let (tail, raw) = raw.parse(list)?;
+    if !tail.is_empty() { return Err(PErr::new(tail)); }
+    let r = raw.into_iter().map(|(typ, data)| extension_data(typ, data, client)).collect::<Result<Vec<_>,_>>()?;
+    Ok((rest, r))
+}
+
+/// parser of ClientHello handshake body, all the body is consumed
+pub fn client_hello(body: &[u8]) -> ParseResult<'_,u8,ClientHello<'_>> {
+    let suites = length_value(take_len_be_u16, more_min(be_u16, 0));
+    let (rest, (legacy_version, random, session_id, cipher_suites, compression_methods)) =
+        tuple((be_u16, take(32), take_record_be_u8, suites, take_record_be_u8)).parse(body).map_err(malformed("malformed ClientHello"))?;
+    let (rest, extensions) = extensions(rest, true).map_err(malformed("malformed ClientHello extensions"))?;
+    if !rest.is_empty() { return Err(PErr::new(rest).user_msg_push(Msg::Str("trailing data in ClientHello"))); }
+    Ok((rest, ClientHello { legacy_version, random, session_id, cipher_suites, compression_methods, extensions }))
+}
+
+/// parser of ServerHello handshake body, all the body is consumed
+pub fn server_hello(body: &[u8]) -> ParseResult<'_,u8,ServerHello<'_>> {
+    let (rest, (legacy_version, random, session_id, cipher_suite, compression_method)) =
+        tuple((be_u16, take(32), take_record_be_u8, be_u16, be_u8)).parse(body).map_err(malformed("malformed ServerHello"))?;
+    let (rest, extensions) = extensions(rest, false).map_err(malformed("malformed ServerHello extensions"))?;
+    if !rest.is_empty() { return Err(PErr::new(rest).user_msg_push(Msg::Str("trailing data in ServerHello"))); }
+    Ok((rest, ServerHello { legacy_version, random, session_id, cipher_suite, compression_method, extensions }))
+}
+
+/// parser of record with ClientHello in it, e.g. first bytes of connection.
+/// ClientHello spread over records is an error, use `handshake_reassemble` then.
+pub fn client_hello_record(input: &[u8]) -> ParseResult<'_,u8,ClientHello<'_>> {
+    let (rest, r) = record(input)?;
+    if r.content_type != HANDSHAKE { return Err(PErr::new(input).user_msg_push(Msg::Str("not a handshake record"))); }
+    let (_, h) = handshake(r.fragment).map_err(|e| malformed("ClientHello spread over records")(in_complete_record(e)))?;
+    if h.msg_type != CLIENT_HELLO { return Err(PErr::new(r.fragment).user_msg_push(Msg::Str("not a ClientHello"))); }
+    let (_, hello) = client_hello(h.body).map_err(in_complete_record)?;
+    Ok((rest, hello))
+}
@@ -83,6 +83,13 @@ pub fn take_len_be_u16(b: &[u8]) -> Result<(&[u8], usize), PErr<u8>> {
 	Ok((new_b, BE::read_u16(c) as usize))
 }
 
+// read Big Endian u16
+pub fn be_u16(b: &[u8]) -> Result<(&[u8], u16), PErr<'_,u8>> {
+	if b.len() < 2 { return Err(PErr::new(b).set_eod()); }
+	let (new_b, c) = split_at_revers(b, 2);
+	Ok((new_b, BE::read_u16(c)))
+}
+
 // read only len Big Endian
 pub fn take_len_be_u24(b: &[u8]) -> Result<(&[u8], usize), PErr<u8>> {
 	if b.len() < 3 { return Err(PErr::new(b).set_eod()); }
@@ -113,8 +120,8 @@ pub fn take_len_be_u64(b: &[u8]) -> Result<(&[u8], usize), PErr<u8>> {
 
 /// read record Big Endian
 pub fn take_record_be_u8(b: &[u8]) -> Result<(&[u8], &[u8]), PErr<u8>> {
-	if b.len() < 2 || b.len() < b[0] as usize { return Err(PErr::new(b).set_eod()); }
-	Ok(split_at_revers(&b[1..], b[0] as usize))
+	let (new_b, l) = take_len_be_u8(b)?;
+	take_record(new_b, l)
 }
 
 /// read record Big Endian
@@ -0,0 +1,329 @@
+//! URI, RFC 3986.
+//! Zero copy: components borrow the input and stay percent-encoded, see `percent_decode`.
+//! `Mode::Strict` accepts the RFC grammar only. `Mode::Lenient` also accepts any visible byte,
+//! e.g. `{`, `|` or UTF-8, and bad percent-encoding, as found in logs and hand written links.
+//! A parser stops at the first byte that can't continue the URI, e.g. a space in a text.
+//!
+//! ```text
+//! let u = uri::parse(b"http://user@example.com:8080/a/b?x=1#top")?;
+//! assert_eq!(Some(&b"example.com"[..]), u.host());
+//! let target = u.resolve(&uri::parse(b"../c")?);
+//! ```
+
+use crate::parser_combinators::{*};
+use crate::u8::is_hex_digit;
+use std::borrow::Cow;
+use std::fmt;
+
+/// grammar check
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum Mode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+/// host of authority
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Host<'a> {
+    /// registered name or IPv4 address
+    RegName(&'a [u8]),
+    /// IPv6 address or IPvFuture, without brackets
+    IpLiteral(&'a [u8]),
+}
+
+impl<'a> Host<'a> {
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self { Host::RegName(x) | Host::IpLiteral(x) => x }
+    }
+}
+
+/// authority `userinfo@host:port`
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Authority<'a> {
+    pub userinfo: Option<&'a [u8]>,
+    pub host: Host<'a>,
+    /// empty port is `None`
+    pub port: Option<u16>,
+}
+
+impl Authority<'_> {
+    fn write_to(&self, r: &mut Vec<u8>) {
+        if let Some(x) = self.userinfo { r.extend_from_slice(x); r.push(b'@'); }
+        match self.host {
+            Host::RegName(x)   => r.extend_from_slice(x),
+            Host::IpLiteral(x) => { r.push(b'['); r.extend_from_slice(x); r.push(b']'); },
+        }
+        if let Some(x) = self.port { r.extend_from_slice(format!(":{x}").as_bytes()); }
+    }
+}
+
+/// URI or relative reference
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Uri<'a> {
+    pub scheme: Option<&'a [u8]>,
+    pub authority: Option<Authority<'a>>,
+    pub path: &'a [u8],
+    pub query: Option<&'a [u8]>,
+    pub fragment: Option<&'a [u8]>,
+}
+
+impl<'a> Uri<'a> {
+    /// relative reference, no scheme
+    pub fn is_relative(&self) -> bool { self.scheme.is_none() }
+    pub fn host(&self) -> Option<&'a [u8]> { self.authority.map(|x| x.host.as_bytes()) }
+    pub fn port(&self) -> Option<u16> { self.authority?.port }
+
+    /// segments of the path without the leading `/`
+    pub fn path_segments(&self) -> impl Iterator<Item = &'a [u8]> {
+        let p = self.path.strip_prefix(b"/").unwrap_or(self.path);
+        p.split(|x| *x == b'/').filter(move |_| !p.is_empty())
+    }
+
+    /// URI text, RFC 3986 5.3
+    pub fn to_vec(&self) -> Vec<u8> {
+        recompose(self.scheme, self.authority.as_ref(), self.path, self.query, self.fragment)
+    }
+
+    /// target URI of the reference with `self` as the base, RFC 3986 5.2
+    pub fn resolve(&self, reference: &Uri) -> Vec<u8> {
+        let r = reference;
+        if r.scheme.is_some() {
+            return recompose(r.scheme, r.authority.as_ref(), &remove_dot_segments(r.path), r.query, r.fragment);
+        }
+        if r.authority.is_some() {
+            return recompose(self.scheme, r.authority.as_ref(), &remove_dot_segments(r.path), r.query, r.fragment);
+        }
+        let (path, query) = if r.path.is_empty() {
+            (self.path.to_vec(), r.query.or(self.query))
+        } else if r.path.starts_with(b"/") {
+            (remove_dot_segments(r.path), r.query)
+        } else {
+            (remove_dot_segments(&self.merge(r.path)), r.query)
+        };
+        recompose(self.scheme, self.authority.as_ref(), &path, query, r.fragment)
+    }
+
+    /// relative path on the base path, RFC 3986 5.2.3
+    fn merge(&self, path: &[u8]) -> Vec<u8> {
+        if self.authority.is_some() && self.path.is_empty() { return [b"/", path].concat(); }
+        let n = self.path.iter().rposition(|x| *x == b'/').map_or(0, |x| x+1);
+        [&self.path[..n], path].concat()
+    }
+}
+
+impl fmt::Display for Uri<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(&self.to_vec()))
+    }
+}
+
+fn recompose(scheme: Option<&[u8]>, authority: Option<&Authority>, path: &[u8], query: Option<&[u8]>, fragment: Option<&[u8]>) -> Vec<u8> {
+    let mut r = Vec::new();
+    if let Some(x) = scheme { r.extend_from_slice(x); r.push(b':'); }
+    if let Some(x) = authority { r.extend_from_slice(b"//"); x.write_to(&mut r); }
+    r.extend_from_slice(path);
+    if let Some(x) = query { r.push(b'?'); r.extend_from_slice(x); }
+    if let Some(x) = fragment { r.push(b'#'); r.extend_from_slice(x); }
+    r
+}
+
+/// remove `.` and `..` segments, RFC 3986 5.2.4
+pub fn remove_dot_segments(path: &[u8]) -> Vec<u8> {
+    fn pop(r: &mut Vec<u8>) { r.truncate(r.iter().rposition(|x| *x == b'/').unwrap_or(0)); }
+    let mut r = Vec::with_capacity(path.len());
+    let mut input = path;
+    while !input.is_empty() {
+        if input.starts_with(b"../") { input = &input[3..]; }
+        else if input.starts_with(b"./") || input.starts_with(b"/./") { input = &input[2..]; }
+        else if input == b"/." { input = b"/"; }
+        else if input.starts_with(b"/../") { input = &input[3..]; pop(&mut r); }
+        else if input == b"/.." { input = b"/"; pop(&mut r); }
+        else if input == b"." || input == b".." { input = b""; }
+        else {
+            let n = input.iter().skip(1).position(|x| *x == b'/').map_or(input.len(), |x| x+1);
+            r.extend_from_slice(&input[..n]);
+            input = &input[n..];
+        }
+    }
+    r
+}
+
+pub fn is_unreserved(i: &u8) -> bool { i.is_ascii_alphanumeric() || b"-._~".contains(i) }
+pub fn is_sub_delim(i: &u8) -> bool { b"!$&'()*+,;=".contains(i) }
+/// char of path segment
+pub fn is_pchar(i: &u8) -> bool { is_unreserved(i) || is_sub_delim(i) || *i == b':' || *i == b'@' }
+fn is_path_char(i: &u8) -> bool { is_pchar(i) || *i == b'/' }
+fn is_query_char(i: &u8) -> bool { is_pchar(i) || *i == b'/' || *i == b'?' }
+fn is_authority_char(i: &u8) -> bool { is_pchar(i) || *i == b'[' || *i == b']' }
+fn is_userinfo_char(i: &u8) -> bool { is_unreserved(i) || is_sub_delim(i) || *i == b':' || *i == b'%' }
+fn is_reg_name_char(i: &u8) -> bool { is_unreserved(i) || is_sub_delim(i) || *i == b'%' }
+fn is_visible(i: &u8) -> bool { *i > 0x20 && *i != 0x7f }
+
+fn hex_byte(h: &[u8]) -> Option<u8> {
+    match h {
+        [a, b] if is_hex_digit(a) && is_hex_digit(b) => {
+            Some(((*a as char).to_digit(16)? * 16 + (*b as char).to_digit(16)?) as u8)
+        },
+        _ => None,
+    }
+}
+
+/// percent-decoding, bad `%` is kept as it is
+pub fn percent_decode(input: &[u8]) -> Cow<'_,[u8]> {
+    if !input.contains(&b'%') { return Cow::Borrowed(input); }
+    let mut r = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while let Some(c) = input.get(i) {
+        match input.get(i+1..i+3).and_then(hex_byte) {
+            Some(x) if *c == b'%' => { r.push(x); i += 3; },
+            _                     => { r.push(*c); i += 1; },
+        }
+    }
+    Cow::Owned(r)
+}
+
+/// percent-decoding to UTF-8 text, `None` if it is not UTF-8
+pub fn percent_decode_utf8(input: &[u8]) -> Option<Cow<'_,str>> {
+    match percent_decode(input) {
+        Cow::Borrowed(x) => std::str::from_utf8(x).ok().map(Cow::Borrowed),
+        Cow::Owned(x)    => String::from_utf8(x).ok().map(Cow::Owned),
+    }
+}
+
+/// percent-encoding of bytes not satisfying `keep`, e.g. `is_unreserved` or `is_pchar`
+pub fn percent_encode(input: &[u8], keep: impl Fn(&u8) -> bool) -> Cow<'_,str> {
+    if input.iter().all(|x| x.is_ascii() && keep(x)) {
+        return Cow::Borrowed(std::str::from_utf8(input).unwrap_or_default());
+    }
+    let mut r = String::with_capacity(input.len()*3);
+    for c in input {
+        if c.is_ascii() && keep(c) { r.push(*c as char); } else { r.push_str(&format!("%{c:02X}")); }
+    }
+    Cow::Owned(r)
+}
+
+/// leading chars of a component, in strict mode `%` must start `%HH`,
+/// in lenient mode visible chars but `stop` are accepted
+fn component<'a>(input: &'a [u8], mode: Mode, allowed: fn(&u8) -> bool, stop: &[u8]) -> ParseResult<'a,u8,&'a [u8]> {
+    let mut i = 0;
+    while let Some(c) = input.get(i) {
+        match mode {
+            Mode::Strict if *c == b'%' => {
+                if input.get(i+1..i+3).and_then(hex_byte).is_none() {
+                    return Err(PErr::msg(&input[i..], "bad percent-encoding"));
+                }
+                i += 3;
+                continue;
+            },
+            Mode::Strict if allowed(c)                        => {},
+            Mode::Lenient if is_visible(c) && !stop.contains(c) => {},
+            _ => break,
+        }
+        i += 1;
+    }
+    Ok(split_at_revers(input, i))
+}
+
+/// parser of scheme with `:`
+fn scheme(input: &[u8]) -> ParseResult<'_,u8,&[u8]> {
+    if !input.first().is_some_and(u8::is_ascii_alphabetic) { return Err(PErr::new(input).expected_push(Msg::Str("scheme"))); }
+    let n = input.iter().take_while(|x| x.is_ascii_alphanumeric() || b"+-.".contains(x)).count();
+    match input.get(n) {
+        Some(b':') => Ok((&input[n+1..], &input[..n])),
+        _          => Err(PErr::new(input).expected_push(Msg::Str("scheme"))),
+    }
+}
+
+/// authority without the leading `//`
+fn authority(input: &[u8], mode: Mode) -> Result<Authority<'_>, PErr<'_,u8>> {
+    let at = match mode {
+        Mode::Strict  => input.iter().position(|x| *x == b'@'),
+        Mode::Lenient => input.iter().rposition(|x| *x == b'@'),
+    };
+    let (userinfo, hostport) = match at {
+        Some(n) => (Some(&input[..n]), &input[n+1..]),
+        None    => (None, input),
+    };
+    let (host, port) = if let Some(literal) = hostport.strip_prefix(b"[") {
+        let n = literal.iter().position(|x| *x == b']').ok_or_else(|| PErr::msg(hostport, "missing `]`"))?;
+        let port = &literal[n+1..];
+        if !port.is_empty() && !port.starts_with(b":") { return Err(PErr::msg(port, "expected `:` or end of authority")); }
+        (Host::IpLiteral(&literal[..n]), port.get(1..))
+    } else {
+        match hostport.iter().rposition(|x| *x == b':') {
+            Some(n) => (Host::RegName(&hostport[..n]), Some(&hostport[n+1..])),
+            None    => (Host::RegName(hostport), None),
+        }
+    };
+    if mode == Mode::Strict {
+        if let Some(x) = userinfo.filter(|x| !x.iter().all(is_userinfo_char)) { return Err(PErr::msg(x, "bad userinfo")); }
+        match host {
+            Host::RegName(x) if !x.iter().all(is_reg_name_char) => return Err(PErr::msg(x, "bad host")),
+            Host::IpLiteral(x) if !is_ip_literal(x)             => return Err(PErr::msg(x, "bad IP literal")),
+            _ => {},
+        }
+    }
+    let port = match port {
+        None | Some([]) => None,
+        Some(x) => {
+            let p = x.iter().all(u8::is_ascii_digit).then(|| std::str::from_utf8(x).ok()?.parse().ok()).flatten();
+            Some(p.ok_or_else(|| PErr::msg(x, "bad port"))?)
+        },
+    };
+    Ok(Authority { userinfo, host, port })
+}
+
+/// IPv6address of RFC 3986 3.2.2 or IPvFuture `v1*HEXDIG.1*(unreserved / sub-delims / :)`
+fn is_ip_literal(x: &[u8]) -> bool {
+    if let Some(f) = x.strip_prefix(b"v").or_else(|| x.strip_prefix(b"V")) {
+        let n = f.iter().take_while(|c| is_hex_digit(c)).count();
+        return n > 0 && f.get(n) == Some(&b'.') && f.len() > n+1
+            && f[n+1..].iter().all(|c| is_unreserved(c) || is_sub_delim(c) || *c == b':');
+    }
+    crate::net::ipv6(x).is_ok_and(|(rest, _)| rest.is_empty())
+}
+
+/// parser of authority, path, query and fragment after the scheme
+fn hier_part<'a>(input: &'a [u8], mode: Mode, scheme: Option<&'a [u8]>) -> ParseResult<'a,u8,Uri<'a>> {
+    let (input, authority) = match input.strip_prefix(b"//") {
+        Some(x) => {
+            let (rest, a) = component(x, mode, is_authority_char, b"/?#")?;
+            (rest, Some(authority(a, mode)?))
+        },
+        None => (input, None),
+    };
+    let (input, path) = component(input, mode, is_path_char, b"?#")?;
+    let (input, query) = match input.strip_prefix(b"?") {
+        Some(x) => { let (rest, q) = component(x, mode, is_query_char, b"#")?; (rest, Some(q)) },
+        None    => (input, None),
+    };
+    let (input, fragment) = match input.strip_prefix(b"#") {
+        Some(x) => { let (rest, f) = component(x, mode, is_query_char, b"")?; (rest, Some(f)) },
+        None    => (input, None),
+    };
+    Ok((input, Uri { scheme, authority, path, query, fragment }))
+}
+
+/// parser of URI, scheme is required
+pub fn uri<'a>(mode: Mode) -> impl Parser<'a,u8,Uri<'a>> {
+    move |input: &'a [u8]| {
+        let (rest, s) = scheme(input)?;
+        hier_part(rest, mode, Some(s))
+    }
+}
+
+/// parser of URI or relative reference
+pub fn uri_reference<'a>(mode: Mode) -> impl Parser<'a,u8,Uri<'a>> {
+    move |input: &'a [u8]| {
+        match scheme(input) {
+            Ok((rest, s)) => hier_part(rest, mode, Some(s)),
+            Err(_)        => hier_part(input, mode, None),
+        }
+    }
+}
+
+/// parse URI reference in strict mode, the whole input
+pub fn parse(input: &[u8]) -> Result<Uri<'_>, ParseError> {
+    uri_reference(Mode::Strict).parse_all(input)
+}
@@ -0,0 +1,71 @@
+use parcelona::parser_combinators::{*};
+use parcelona::bnf::Grammar;
+
+const ABNF: &[u8] = br#"
+; simple key value list
+list     = entry *("," *SP entry)
+entry    = key "=" value
+key      = ALPHA *(ALPHA / DIGIT / "-")
+value    = 1*DIGIT / DQUOTE *(%x20-21 / %x23-7E) DQUOTE
+hex      = "0x" 2*4HEXDIG
+method   = %s"GET" / %s"POST"
+method   =/ %s"PUT"
+crlf-end = 1*%x61.62 CRLF
+"#;
+
+#[test]
+fn t_abnf() {
+    let g = Grammar::from_abnf(ABNF).unwrap();
+    let list = g.rule("list").unwrap();
+    assert_eq!(Ok((b";".as_slice(), b"a=1, b-2=\"x y\",c=33".as_slice())), list.parse(b"a=1, b-2=\"x y\",c=33;"));
+    assert_eq!(Ok(b"0xfF1".as_slice()), g.rule("HEX").unwrap().parse_all(b"0xfF1"));
+    assert!(g.rule("hex").unwrap().parse_all(b"0xf").is_err());
+    assert!(g.rule("hex").unwrap().parse_all(b"0xfffff").is_err());
+    assert!(g.rule("method").unwrap().parse_all(b"PUT").is_ok());
+    assert!(g.rule("method").unwrap().parse_all(b"get").is_err());
+    assert_eq!(Ok(b"ababab\r\n".as_slice()), g.rule("crlf-end").unwrap().parse_all(b"ababab\r\n"));
+    assert!(g.rule_names().iter().any(|x| x == "ALPHA"));
+}
+
+#[test]
+fn t_abnf_errors() {
+    let g = Grammar::from_abnf(ABNF).unwrap();
+    let e = g.rule("entry").unwrap().parse(b"a=\"x").unwrap_err();
+    let msg: Vec<&str> = e.get_msg().iter().map(|x| x.as_str()).collect();
+    assert_eq!(vec!["rule `DQUOTE` failed", "rule `value` failed", "rule `entry` failed"], msg);
+    assert!(e.is_eod());
+
+    let e = Grammar::from_abnf(b"a = b c\nb = \"x\"\n").unwrap_err();
+    assert_eq!(Some(6), e.offset());
+    assert_eq!(["rule `c` is not defined".to_string()].as_slice(), e.get_msg());
+
+    let e = Grammar::from_abnf(b"a = *\"x\" b\nb = a \"y\" / \"z\"\n").unwrap_err();
+    assert_eq!(["rule `a` is left recursive".to_string()].as_slice(), e.get_msg());
+
+    assert!(Grammar::from_abnf(b"a = <prose>\n").is_err());
+    let e = Grammar::from_abnf(b"a = %x80-10FFFF\n").unwrap_err();
+    assert_eq!(Some(9), e.offset());
+    assert!(e.get_msg().iter().any(|x| x == "num-val above 0xFF is not supported"));
+    assert!(Grammar::from_abnf(b"a = %d256\n").is_err());
+    assert!(Grammar::from_abnf(b"a = \"x\"\na = \"y\"\n").is_err());
+}
+
+const EBNF: &[u8] = br#"
+/* numbers and words */
+Number ::= '-'? Digit+ ('.' Digit+)?
+Digit  ::= [0-9]
+Word   ::= [a-zA-Z_] [^ #x20#x09,]*
+Str    ::= '"' ([^"] - '\')* '"'
+List   ::= (Number | Word | Str) (',' (Number | Word | Str))*
+"#;
+
+#[test]
+fn t_ebnf() {
+    let g = Grammar::from_ebnf(EBNF).unwrap();
+    assert_eq!(Ok(b"-12.5".as_slice()), g.rule("Number").unwrap().parse_all(b"-12.5"));
+    assert!(g.rule("Number").unwrap().parse_all(b"12.").is_err());
+    assert_eq!(Ok((b" x".as_slice(), b"_ab-c".as_slice())), g.rule("Word").unwrap().parse(b"_ab-c x"));
+    assert!(g.rule("Str").unwrap().parse_all(br#""ab\c""#).is_err());
+    assert_eq!(Ok(br#"1,abc,"q q",-2"#.as_slice()), g.rule("List").unwrap().parse_all(br#"1,abc,"q q",-2"#));
+    assert!(g.rule("number").is_none());
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "tokio")]
+use parcelona::parser_combinators::{*};
+use parcelona::codec::{*};
+use parcelona::u8::{*};
+use futures_util::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+fn record(input: &[u8]) -> ParseResult<'_,u8,Vec<u8>> {
+    let (input, r) = take_record_be_u16(input)?;
+    Ok((input, r.to_vec()))
+}
+
+fn line(input: &[u8]) -> ParseResult<'_,u8,String> {
+    let (input, r) = left(seq(is_alphanum), starts_with(b"\n")).parse(input)?;
+    Ok((input, String::from_utf8_lossy(r).into_owned()))
+}
+
+#[tokio::test]
+async fn t_frame_reader() {
+    let (mut tx, rx) = tokio::io::duplex(4);
+    let writer = tokio::spawn(async move {
+        tx.write_all(b"\x00\x03abc\x00\x00\x00\x05hello").await.unwrap();
+    });
+    let frames: Vec<Vec<u8>> = frame_reader(rx, record).map(|x| x.unwrap()).collect().await;
+    writer.await.unwrap();
+    assert_eq!(vec![b"abc".to_vec(), vec![], b"hello".to_vec()], frames);
+}
+
+#[tokio::test]
+async fn t_frame_errors() {
+    let (mut tx, rx) = tokio::io::duplex(64);
+    tx.write_all(b"ab\ncd\n-x\n").await.unwrap();
+    drop(tx);
+    let mut frames = frame_reader(rx, line);
+    assert_eq!("ab", frames.next().await.unwrap().unwrap());
+    assert_eq!("cd", frames.next().await.unwrap().unwrap());
+    match frames.next().await {
+        Some(Err(FrameError::Parse(e))) => assert_eq!(Some(0), e.offset()),
+        r => panic!("{:?}", r),
+    }
+
+    let (mut tx, rx) = tokio::io::duplex(64);
+    tx.write_all(b"ab\ncd").await.unwrap();
+    drop(tx);
+    let mut frames = frame_reader(rx, line);
+    assert_eq!("ab", frames.next().await.unwrap().unwrap());
+    assert!(matches!(frames.next().await, Some(Err(FrameError::Incomplete(2)))));
+
+    let (mut tx, rx) = tokio::io::duplex(64);
+    tx.write_all(b"abcdefgh").await.unwrap();
+    let mut frames = tokio_util::codec::FramedRead::new(rx, FrameDecoder::new(line).max_len(4));
+    assert!(matches!(frames.next().await, Some(Err(FrameError::TooLong(4)))));
+}
@@ -0,0 +1,102 @@
+use parcelona::config::{*};
+use parcelona::report::Report;
+
+const TOML: &[u8] = br#"# service
+title = "demo \"app\"\tv\u00e9"
+path = 'C:\tmp'
+debug = true
+
+[server]
+host = "localhost"  # comment
+port = 8_080
+ratio = 0.5
+limits.max = 1e3
+tags = [
+  "a", 'b',  # first
+  "c",
+]
+
+[server.tls]
+enabled = false
+hex = 0xff
+neg = -17
+"quoted key" = [[1, 2], [3.5]]
+"#;
+
+#[test]
+fn t_config_toml() {
+    let doc = parse(TOML).unwrap();
+    assert_eq!(Some("demo \"app\"\tvé"), doc.get("title").and_then(|x| x.value.as_str()));
+    assert_eq!(Some(r"C:\tmp"), doc.get("path").and_then(|x| x.value.as_str()));
+    assert_eq!(Some(true), doc.get("debug").and_then(|x| x.value.as_bool()));
+    assert_eq!(Some("localhost"), doc.get_path("server.host").and_then(|x| x.value.as_str()));
+    assert_eq!(Some(8080), doc.get_path("server.port").and_then(|x| x.value.as_i64()));
+    assert_eq!(Some(0.5), doc.get_path("server.ratio").and_then(|x| x.value.as_f64()));
+    assert_eq!(Some(1000.0), doc.get_path("server.limits.max").and_then(|x| x.value.as_f64()));
+    let tags: Vec<_> = doc.get_path("server.tags").unwrap().value.as_array().unwrap().iter().map(|x| x.value.as_str().unwrap()).collect();
+    assert_eq!(vec!["a", "b", "c"], tags);
+    let tls = doc.get_path("server.tls").unwrap().value.as_table().unwrap();
+    assert_eq!(vec!["enabled", "hex", "neg", "quoted key"], tls.iter().map(|x| x.0).collect::<Vec<_>>());
+    assert_eq!(Some(255), tls.get("hex").and_then(|x| x.value.as_i64()));
+    assert_eq!(Some(-17), tls.get("neg").and_then(|x| x.value.as_i64()));
+    let nested = tls.get("quoted key").unwrap().value.as_array().unwrap();
+    assert_eq!(Some(3.5), nested[1].value.as_array().unwrap()[0].value.as_f64());
+    // the root has keys and sections in order
+    assert_eq!(vec!["title", "path", "debug", "server"], doc.iter().map(|x| x.0).collect::<Vec<_>>());
+}
+
+#[test]
+fn t_config_spans() {
+    let doc = parse(TOML).unwrap();
+    let port = doc.get_path("server.port").unwrap();
+    assert_eq!(8, port.span.line);
+    assert_eq!(b"8_080", &TOML[port.span.start..port.span.end]);
+    assert_eq!(11, doc.get_path("server.tags").unwrap().span.line);
+    let c = &doc.get_path("server.tags").unwrap().value.as_array().unwrap()[2];
+    assert_eq!((13, &b"\"c\""[..]), (c.span.line, &TOML[c.span.start..c.span.end]));
+    assert_eq!(16, doc.get_path("server.tls").unwrap().span.line);
+    assert_eq!(6, doc.get("server").unwrap().span.line);
+}
+
+#[test]
+fn t_config_ini() {
+    let text = b"; settings\r\n[database]\r\nuser = admin\r\npassword: p#ss ; secret\r\nport = 5432\r\nname = \"main db\"\r\nempty =\r\n";
+    assert!(parse(text).is_err());
+    let doc = Config::ini().parse(text).unwrap();
+    let db = doc.get("database").unwrap().value.as_table().unwrap();
+    assert_eq!(Some("admin"), db.get("user").and_then(|x| x.value.as_str()));
+    assert_eq!(Some("p#ss"), db.get("password").and_then(|x| x.value.as_str()));
+    assert_eq!(Some(5432), db.get("port").and_then(|x| x.value.as_i64()));
+    assert_eq!(Some("main db"), db.get("name").and_then(|x| x.value.as_str()));
+    assert_eq!(Some(""), db.get("empty").and_then(|x| x.value.as_str()));
+    assert_eq!(5, db.get("port").unwrap().span.line);
+
+    let text = b"[ My Section ]\nlog level = debug\nmax  users : 10\n[a b.c]\nx=1\n";
+    assert!(parse(text).is_err());
+    let doc = Config::ini().parse(text).unwrap();
+    assert_eq!(Some("debug"), doc.get_path("My Section.log level").and_then(|x| x.value.as_str()));
+    assert_eq!(Some(10), doc.get_path("My Section.max  users").and_then(|x| x.value.as_i64()));
+    assert_eq!(Some(1), doc.get_path("a b.c.x").and_then(|x| x.value.as_i64()));
+}
+
+#[test]
+fn t_config_errors() {
+    let line = |text: &[u8]| {
+        let e = parse(text).unwrap_err();
+        Report::new(text).line_col(e.offset().unwrap()).0
+    };
+    assert_eq!(2, line(b"a = 1\nb = x2\n"));
+    assert_eq!(3, line(b"a = 1\n\na = 2\n"));
+    assert_eq!(2, line(b"[s]\n[s]\n"));
+    assert_eq!(2, line(b"a = 1\na.b = 2\n"));
+    assert_eq!(1, line(b"a = \"open\nb = 1\n"));
+    assert_eq!(1, line(b"a = 1 b\n"));
+    assert_eq!(1, line(b"a = \"\\q\"\n"));
+    assert_eq!(3, line(b"a = [\n1,\n2 3]\n"));
+    for x in ["a = 01", "a = 1__0", "a = _1", "a = 1.", "a = .5", "a = 1e", "[[t]]", "a = {b = 1}", "= 1", "a = [1, 2"] {
+        assert!(parse(x.as_bytes()).is_err(), "{x}");
+    }
+    assert!(parse(b"a = [1, 2").unwrap_err().is_eod());
+    assert_eq!(Some(f64::NEG_INFINITY), parse(b"a = -inf").unwrap().get("a").and_then(|x| x.value.as_f64()));
+    assert_eq!(Some(-0.01), parse(b"a = -1E-2").unwrap().get("a").and_then(|x| x.value.as_f64()));
+}
@@ -0,0 +1,75 @@
+use parcelona::parser_combinators::{*};
+use parcelona::context::{*};
+use parcelona::u8::{*};
+
+#[test]
+fn t_ctx_header_flags() {
+    // flag byte: 1 - length is u16, 0 - length is u8
+    let data: &[u8] = &[1, 0, 3, 7, 8, 9, 0, 1, 5];
+    let flag = ctx_with(ctx_lift(take(1)), |st:&mut bool, r:&&[u8]| { *st = r[0]==1; });
+    let body = ctx_parser(|wide:&bool| {
+        if *wide { take_record_be_u16 as fn(&[u8]) -> ParseResult<u8,&[u8]> } else { take_record_be_u8 }
+    });
+    let frame = ctx_right(flag, body);
+
+    let mut st = false;
+    let (data, r) = frame.parse_ctx(data, &mut st).unwrap();
+    assert_eq!(b"\x07\x08\x09".as_slice(), r);
+    assert!(st);
+    let (data, r) = frame.parse_ctx(data, &mut st).unwrap();
+    assert_eq!(b"\x05".as_slice(), r);
+    assert!(!st);
+    assert!(data.is_empty());
+}
+
+#[test]
+fn t_ctx_indent() {
+    let data: &[u8] = b"a\n  b\n  c\nd\n";
+    let indent = |input:&'static [u8], st:&mut usize| {
+        let (input, sp) = option(seq(is_space_noeol)).parse(input)?;
+        if sp.map_or(0, <[u8]>::len) == *st { Ok((input, ())) } else { Err(PErr::new(input)) }
+    };
+    let line = ctx_right(indent, ctx_lift(left(seq(is_alpha), any(b"\n"))));
+    let block = ctx_pair(line, ctx_scope(|st:&mut usize| *st += 2, ctx_more(line)));
+
+    let mut st = 0_usize;
+    let (data, (head, body)) = block.parse_ctx(data, &mut st).unwrap();
+    assert_eq!(b"a".as_slice(), head);
+    assert_eq!(vec![b"b".as_slice(), b"c".as_slice()], body);
+    assert_eq!(0, st);
+    assert_eq!(b"d\n".as_slice(), data);
+}
+
+#[test]
+fn t_ctx_alt_restore() {
+    let data: &[u8] = b"xy";
+    let p1 = ctx_right(ctx_set(|st:&mut u32| *st = 10), ctx_lift(starts_with(b"z")));
+    let p2 = ctx_right(ctx_check(|st:&u32| *st == 0), ctx_lift(starts_with(b"x")));
+    let mut st = 0_u32;
+    let r = ctx_alt(p1, p2).parse_ctx(data, &mut st).unwrap();
+    assert_eq!((b"y".as_slice(), b"x".as_slice()), r);
+    assert_eq!(0, st);
+
+    let r = ctx_option(p1).parse_ctx(data, &mut st).unwrap();
+    assert_eq!((data, None), r);
+    assert_eq!(0, ctx_get(|st:&u32| *st).parse_ctx(data, &mut st).unwrap().1);
+}
+
+#[test]
+fn t_ctx_source() {
+    // offset table: first byte is the offset of the word
+    let data = b"\x04abcword";
+    let word = ctx_lift(seq(is_alpha));
+    let p = |input: &'static [u8], src: &mut Source<'static,u8>| {
+        let (rest, offset) = take_len_be_u8(input)?;
+        ctx_at(offset, word).parse_ctx(rest, src)
+    };
+    let mut src = Source::new(&data[..]);
+    let (rest, r) = p.parse_ctx(data, &mut src).unwrap();
+    assert_eq!(b"word", r);
+    assert_eq!(Some(1), src.offset(rest));
+    assert_eq!(None, src.offset(b"other"));
+    assert!(ctx_at(100, word).parse_ctx(data, &mut src).is_err());
+    let data = b"\x01abcword";
+    assert_eq!(b"abcword", p.parse_ctx(data, &mut Source::new(&data[..])).unwrap().1);
+}
@@ -0,0 +1,58 @@
+use parcelona::parser_combinators::{*};
+use parcelona::csv::{*};
+use std::borrow::Cow;
+
+fn fields<'a>(r: &'a Record) -> Vec<&'a [u8]> { r.iter().map(|x| x.as_ref()).collect() }
+
+#[test]
+fn t_csv_rfc4180() {
+    let data = b"name,comment\r\nbob,\"says \"\"hi\"\"\"\r\n\"multi\r\nline\",\"a,b\"\r\n,\n\"last\",x";
+    let mut rd = Csv::new().header(true).reader(data);
+    assert_eq!(vec![b"name".as_slice(), b"comment"], fields(&rd.header().unwrap().to_vec()));
+    assert_eq!(Some(1), rd.column(b"comment"));
+
+    let r = rd.next().unwrap().unwrap();
+    assert_eq!(vec![b"bob".as_slice(), b"says \"hi\""], fields(&r));
+    assert!(matches!(r[0], Cow::Borrowed(_)));
+    assert!(matches!(r[1], Cow::Owned(_)));
+
+    let r = rd.next().unwrap().unwrap();
+    assert_eq!(vec![b"multi\r\nline".as_slice(), b"a,b"], fields(&r));
+    assert!(r.iter().all(|x| matches!(x, Cow::Borrowed(_))));
+    assert_eq!(vec![b"".as_slice(), b""], fields(&rd.next().unwrap().unwrap()));
+    assert_eq!(vec![b"last".as_slice(), b"x"], fields(&rd.next().unwrap().unwrap()));
+    assert!(rd.next().is_none());
+}
+
+#[test]
+fn t_csv_dialect() {
+    let csv = Csv::new().delimiter(b';').quote(b'\'').escape(Some(b'\\'));
+    let rows: Vec<Record> = csv.reader(b"1;'it\\'s';'a''b'\n2;;\n").map(|x| x.unwrap()).collect();
+    assert_eq!(2, rows.len());
+    assert_eq!(vec![b"1".as_slice(), b"it's", b"a'b"], fields(&rows[0]));
+    assert_eq!(vec![b"2".as_slice(), b"", b""], fields(&rows[1]));
+
+    let p = more(Csv::new().record());
+    let (rest, r) = p.parse(b"a,b\nc,d\n").unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(2, r.len());
+}
+
+#[test]
+fn t_csv_errors() {
+    let mut rd = Csv::new().reader(b"a,b\n\"c\"x,d\ne,f\n");
+    assert!(rd.next().unwrap().is_ok());
+    let e = rd.next().unwrap().unwrap_err();
+    assert_eq!(b"x,d\ne,f\n".as_slice(), e.get_input());
+    assert!(rd.next().is_none());
+    assert_eq!(b"\"c\"x,d\ne,f\n".as_slice(), rd.remaining());
+
+    let e = Csv::new().record().parse(b"a,\"open").unwrap_err();
+    assert!(e.is_eod());
+
+    let mut rd = Csv::new().header(true).reader(b"\"a\"x,b\n1,2\n");
+    assert!(rd.header().is_none());
+    assert_eq!(b"x,b\n1,2\n".as_slice(), rd.next().unwrap().unwrap_err().get_input());
+    assert!(rd.next().is_none());
+    assert!(Csv::new().header(true).reader(b"").next().is_none());
+}
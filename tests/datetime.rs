@@ -0,0 +1,89 @@
+use parcelona::datetime::{*};
+
+fn dt((year, month, day): (u16, u8, u8), (hour, minute, second, nanosecond): (u8, u8, u8, u32), offset: Option<i16>) -> DateTime {
+    DateTime { date: Date { year, month, day }, time: Time { hour, minute, second, nanosecond }, offset }
+}
+
+fn msg(e: parcelona::parser_combinators::PErr<u8>) -> String { format!("{:?}", e.get_msg()) }
+
+#[test]
+fn t_datetime_rfc3339() {
+    // RFC 3339 5.8
+    assert_eq!(Ok((&b""[..], dt((1985, 4, 12), (23, 20, 50, 520_000_000), Some(0)))), rfc3339(b"1985-04-12T23:20:50.52Z"));
+    assert_eq!(dt((1996, 12, 19), (16, 39, 57, 0), Some(-8*60)), rfc3339(b"1996-12-19T16:39:57-08:00").unwrap().1);
+    assert_eq!(dt((1990, 12, 31), (23, 59, 60, 0), Some(0)), rfc3339(b"1990-12-31T23:59:60Z").unwrap().1);
+    assert_eq!(dt((1990, 12, 31), (15, 59, 60, 0), Some(-8*60)), rfc3339(b"1990-12-31T15:59:60-08:00").unwrap().1);
+    assert_eq!(dt((1937, 1, 1), (12, 0, 27, 870_000_000), Some(20)), rfc3339(b"1937-01-01T12:00:27.87+00:20").unwrap().1);
+    assert_eq!(None, rfc3339(b"2024-01-01t00:00:00-00:00").unwrap().1.offset);
+    assert_eq!(123_456_789, rfc3339(b"2024-01-01 00:00:00.1234567891Z").unwrap().1.time.nanosecond);
+    assert_eq!(Ok((&b" msg"[..], dt((2024, 2, 29), (0, 0, 0, 0), Some(0)))), rfc3339(b"2024-02-29T00:00:00Z msg"));
+
+    for (x, m) in [
+        ("2023-02-29T00:00:00Z", "day is out of range"), ("2024-13-01T00:00:00Z", "month is out of range"),
+        ("2024-00-01T00:00:00Z", "month is out of range"), ("2024-04-31T00:00:00Z", "day is out of range"),
+        ("2024-01-01T24:00:00Z", "hour is out of range"), ("2024-01-01T00:60:00Z", "minute is out of range"),
+        ("2024-01-01T00:00:61Z", "second is out of range"), ("2024-01-01T23:58:60Z", "leap second"),
+        ("2024-01-01T00:00:00+24:00", "offset is out of range"),
+    ] {
+        assert!(msg(rfc3339(x.as_bytes()).unwrap_err()).contains(m), "{x}");
+    }
+    for x in ["2024-01-01T00:00:00", "2024-01-01", "24-01-01T00:00:00Z", "2024-01-01T00:00Z", "2024-01-01T00:00:00.Z"] {
+        assert!(rfc3339(x.as_bytes()).is_err(), "{x}");
+    }
+}
+
+#[test]
+fn t_datetime_iso8601() {
+    assert_eq!(dt((2024, 3, 5), (7, 8, 9, 0), Some(0)), iso8601(b"20240305T070809Z").unwrap().1);
+    assert_eq!(dt((2024, 3, 5), (7, 8, 9, 500_000_000), Some(90)), iso8601(b"2024-03-05T07:08:09,5+01:30").unwrap().1);
+    assert_eq!(dt((2024, 3, 5), (7, 8, 9, 0), Some(-5*60)), iso8601(b"2024-03-05T07:08:09-05").unwrap().1);
+    assert_eq!(dt((2024, 3, 5), (7, 8, 9, 0), Some(130)), iso8601(b"20240305T070809+0210").unwrap().1);
+    assert_eq!(Ok((&b" x"[..], dt((2024, 3, 5), (7, 8, 9, 0), None))), iso8601(b"2024-03-05T07:08:09 x"));
+    assert!(iso8601(b"2024-0305T070809Z").is_err());
+}
+
+#[test]
+fn t_datetime_rfc2822() {
+    // RFC 2822 A.1, A.5 and obsolete forms of A.6
+    assert_eq!(dt((1997, 11, 21), (9, 55, 6, 0), Some(-6*60)), rfc2822(b"Fri, 21 Nov 1997 09:55:06 -0600").unwrap().1);
+    assert_eq!(dt((2003, 7, 1), (10, 52, 37, 0), Some(2*60)), rfc2822(b"Tue, 1 Jul 2003 10:52:37 +0200").unwrap().1);
+    assert_eq!(dt((1969, 2, 13), (23, 32, 0, 0), Some(-210)), rfc2822(b"Thu,\r\n 13\r\n   Feb\r\n   1969\r\n 23:32\r\n  -0330 (Newfoundland Time)").unwrap().1);
+    assert_eq!(dt((1997, 11, 21), (9, 55, 6, 0), Some(-8*60)), rfc2822(b"21 Nov 97 09:55:06 PST").unwrap().1);
+    assert_eq!(dt((2003, 7, 1), (10, 52, 37, 0), Some(0)), rfc2822(b"1 jul 03 10:52:37 GMT").unwrap().1);
+    assert_eq!(None, rfc2822(b"1 Jul 2003 10:52:37 -0000").unwrap().1.offset);
+    assert_eq!(None, rfc2822(b"1 Jul 2003 10:52:37 Z").unwrap().1.offset);
+    assert_eq!(b"\r\n", rfc2822(b"1 Jul 2003 10:52:37 +0000\r\n").unwrap().0);
+    assert!(msg(rfc2822(b"31 Jun 2003 10:52:37 +0000").unwrap_err()).contains("day is out of range"));
+    assert!(rfc2822(b"1 Jux 2003 10:52:37 +0000").is_err());
+    assert!(rfc2822(b"1 Jul 2003 10:52:37 XYZ").is_err());
+}
+
+#[test]
+fn t_datetime_http_date() {
+    // RFC 9110 5.6.7
+    let t = dt((1994, 11, 6), (8, 49, 37, 0), Some(0));
+    assert_eq!(Ok((&b""[..], t)), http_date(b"Sun, 06 Nov 1994 08:49:37 GMT"));
+    assert_eq!(Ok((&b""[..], t)), http_date(b"Sunday, 06-Nov-94 08:49:37 GMT"));
+    assert_eq!(Ok((&b""[..], t)), http_date(b"Sun Nov  6 08:49:37 1994"));
+    assert_eq!(2016, http_date(b"Monday, 01-Feb-16 00:00:00 GMT").unwrap().1.date.year);
+    assert_eq!(Ok((&b"\r\n"[..], dt((2024, 12, 24), (1, 2, 3, 0), Some(0)))), http_date(b"Tue Dec 24 01:02:03 2024\r\n"));
+    for x in ["Sun, 06 Nov 1994 08:49:37 UTC", "Sun, 6 Nov 1994 08:49:37 GMT", "Sun, 31 Nov 1994 08:49:37 GMT", "Sun Nov 6 08:49:37 1994"] {
+        assert!(http_date(x.as_bytes()).is_err(), "{x}");
+    }
+}
+
+#[test]
+fn t_datetime_calendar() {
+    assert_eq!(784111777, http_date(b"Sun, 06 Nov 1994 08:49:37 GMT").unwrap().1.unix_timestamp());
+    assert_eq!(0, rfc3339(b"1970-01-01T00:00:00Z").unwrap().1.unix_timestamp());
+    assert_eq!(0, rfc3339(b"1970-01-01T01:00:00+01:00").unwrap().1.unix_timestamp());
+    assert_eq!(-1, rfc3339(b"1969-12-31T23:59:59Z").unwrap().1.unix_timestamp());
+    assert_eq!(951782400, rfc3339(b"2000-02-29T00:00:00Z").unwrap().1.unix_timestamp());
+    assert_eq!(7, Date { year: 1994, month: 11, day: 6 }.weekday());
+    assert_eq!(4, Date { year: 1970, month: 1, day: 1 }.weekday());
+    assert!(is_leap_year(2000) && !is_leap_year(1900) && is_leap_year(2024));
+    assert_eq!(None, Date::new(2023, 2, 29));
+    assert_eq!("1985-04-12T23:20:50.52Z", rfc3339(b"1985-04-12T23:20:50.52Z").unwrap().1.to_string());
+    assert_eq!("1996-12-19T16:39:57-08:00", rfc3339(b"1996-12-19T16:39:57-08:00").unwrap().1.to_string());
+    assert_eq!("2024-01-01T00:00:00-00:00", rfc3339(b"2024-01-01T00:00:00-00:00").unwrap().1.to_string());
+}
@@ -0,0 +1,114 @@
+use parcelona::dns::{*};
+use std::net::{Ipv4Addr,Ipv6Addr};
+
+fn u16b(x: u16) -> Vec<u8> { x.to_be_bytes().to_vec() }
+fn rr(name: &[u8], typ: u16, data: &[u8]) -> Vec<u8> {
+    [name.to_vec(), u16b(typ), u16b(CLASS_IN), 300u32.to_be_bytes().to_vec(), u16b(data.len() as u16), data.to_vec()].concat()
+}
+fn head(flags: u16, counts: [u16; 4]) -> Vec<u8> {
+    [u16b(0x1234), u16b(flags), counts.iter().flat_map(|x| u16b(*x)).collect()].concat()
+}
+
+// offset 12 is `example.com` of the question
+const EXAMPLE: &[u8] = b"\xc0\x0c";
+const QUESTION: &[u8] = b"\x07example\x03com\x00\x00\x01\x00\x01";
+
+fn response() -> Vec<u8> {
+    let answers = [
+        rr(EXAMPLE, TYPE_A, &[93, 184, 216, 34]),
+        rr(b"\x03www\xc0\x0c", TYPE_CNAME, EXAMPLE),
+        rr(EXAMPLE, TYPE_MX, &[&u16b(10)[..], b"\x04mail\xc0\x0c"].concat()),
+        rr(EXAMPLE, TYPE_TXT, b"\x05hello\x00"),
+        rr(EXAMPLE, TYPE_AAAA, &[0x20, 1, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    ].concat();
+    let soa = [b"\x02ns\xc0\x0c\x0ahostmaster\xc0\x0c".to_vec(), [1u32, 2, 3, 4, 5].iter().flat_map(|x| x.to_be_bytes()).collect()].concat();
+    let authority = rr(EXAMPLE, TYPE_SOA, &soa);
+    let additional = rr(b"\x00", 41, b"\x01\x02");
+    [head(0x8180, [1, 5, 1, 1]), QUESTION.to_vec(), answers, authority, additional].concat()
+}
+
+#[test]
+fn t_dns_query() {
+    let data = [head(0x0100, [1, 0, 0, 0]), QUESTION.to_vec()].concat();
+    let m = parse(&data).unwrap();
+    assert_eq!(0x1234, m.header.id);
+    assert!(!m.header.qr());
+    assert!(m.header.rd());
+    assert_eq!(0, m.header.opcode());
+    assert_eq!(1, m.questions.len());
+    assert_eq!("example.com", m.questions[0].name.to_string());
+    assert_eq!((TYPE_A, CLASS_IN), (m.questions[0].qtype, m.questions[0].qclass));
+    assert!(m.answers.is_empty());
+}
+
+#[test]
+fn t_dns_response() {
+    let data = response();
+    let m = parse(&data).unwrap();
+    assert!(m.header.qr() && m.header.rd() && m.header.ra() && !m.header.aa() && !m.header.tc());
+    assert_eq!(0, m.header.rcode());
+    let a = &m.answers;
+    assert_eq!(5, a.len());
+    assert_eq!("example.com", a[0].name.to_string());
+    assert_eq!(300, a[0].ttl);
+    assert_eq!(RData::A(Ipv4Addr::new(93, 184, 216, 34)), a[0].data);
+    assert_eq!("www.example.com", a[1].name.to_string());
+    let RData::Cname(n) = &a[1].data else { panic!() };
+    assert_eq!("example.com", n.to_string());
+    let RData::Mx { preference, exchange } = &a[2].data else { panic!() };
+    assert_eq!((10, "mail.example.com".to_string()), (*preference, exchange.to_string()));
+    assert_eq!(RData::Txt(vec![&b"hello"[..], b""]), a[3].data);
+    assert_eq!(RData::Aaaa("2001:db8::1".parse::<Ipv6Addr>().unwrap()), a[4].data);
+    let RData::Soa { mname, rname, serial, minimum, .. } = &m.authorities[0].data else { panic!() };
+    assert_eq!(("ns.example.com", "hostmaster.example.com"), (&*mname.to_string(), &*rname.to_string()));
+    assert_eq!((1, 5), (*serial, *minimum));
+    assert!(m.additionals[0].name.is_root());
+    assert_eq!(RData::Unknown(b"\x01\x02"), m.additionals[0].data);
+}
+
+#[test]
+fn t_dns_tcp() {
+    let data = response();
+    let framed = [u16b(data.len() as u16), data.clone(), b"next".to_vec()].concat();
+    let (rest, m) = tcp_message(&framed).unwrap();
+    assert_eq!(b"next", rest);
+    assert_eq!(5, m.answers.len());
+    // incomplete frame
+    assert!(tcp_message(&framed[..framed.len()-10]).unwrap_err().is_eod());
+}
+
+#[test]
+fn t_dns_pointer_loop() {
+    // pointer to itself
+    let data = [head(0, [1, 0, 0, 0]), b"\xc0\x0c\x00\x01\x00\x01".to_vec()].concat();
+    assert!(message(&data).is_err());
+    // two pointers to each other: the second points forward
+    let data = [head(0, [2, 0, 0, 0]), b"\x01a\xc0\x12\x00\x01\x00\x01\xc0\x0c\x00\x01\x00\x01".to_vec()].concat();
+    let e = message(&data).unwrap_err();
+    assert!(!e.is_eod());
+    // pointer out of the message
+    let data = [head(0, [1, 0, 0, 0]), b"\xff\xff\x00\x01\x00\x01".to_vec()].concat();
+    assert!(message(&data).is_err());
+}
+
+#[test]
+fn t_dns_bad_names() {
+    // label type 0x40
+    let data = [head(0, [1, 0, 0, 0]), b"\x41a\x00\x00\x01\x00\x01".to_vec()].concat();
+    assert!(!message(&data).unwrap_err().is_eod());
+    // name over 255 bytes
+    let long: Vec<u8> = (0..5).flat_map(|_| [&[63u8][..], &[b'a'; 63]].concat()).collect();
+    let data = [head(0, [1, 0, 0, 0]), long, b"\x00\x00\x01\x00\x01".to_vec()].concat();
+    assert!(!message(&data).unwrap_err().is_eod());
+}
+
+#[test]
+fn t_dns_truncated() {
+    let data = response();
+    for n in [0, 5, 12, 20, 40, data.len()-1] {
+        assert!(message(&data[..n]).unwrap_err().is_eod(), "{n}");
+    }
+    // record data shorter than its type needs
+    let data = [head(0x8000, [0, 1, 0, 0]), rr(b"\x00", TYPE_A, &[1, 2, 3])].concat();
+    assert!(!message(&data).unwrap_err().is_eod());
+}
@@ -0,0 +1,47 @@
+use parcelona::parser_combinators::{*};
+use parcelona::dynamic::{*};
+use parcelona::u8::{*};
+use std::sync::Arc;
+
+#[test]
+fn t_alt_dyn() {
+    let detectors: Vec<BoxParser<u8,&[u8]>> = vec![
+        starts_with(b"GET").boxed(),
+        starts_with(b"POST").boxed(),
+        BoxParser::new(seq_exact(is_dec_digit,3)),
+    ];
+    let p = alt_dyn(&detectors);
+    assert_eq!(Ok((b" /".as_slice(), b"POST".as_slice())), p.parse(b"POST /"));
+    assert_eq!(Ok((b"4".as_slice(), b"123".as_slice())), p.parse(b"1234"));
+    assert!(p.parse(b"PUT /").is_err());
+
+    let first = &detectors[0];
+    assert_eq!(Ok((b"/".as_slice(), (b"GET".as_slice(), b" ".as_slice()))), pair(first, seq(is_space)).parse(b"GET /"));
+}
+
+#[test]
+fn t_permut_dyn() {
+    let data: &[u8] = b"truefalse12";
+    let parts: Vec<RcParser<u8,&[u8]>> = vec![
+        RcParser::new(starts_with(b"false")),
+        RcParser::new(seq(is_dec_digit)),
+        RcParser::new(starts_with(b"true")),
+    ];
+    let (rest, r) = permut_dyn(&parts).parse(data).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(vec![b"false".as_slice(), b"12".as_slice(), b"true".as_slice()], r);
+
+    let shared = parts[1].clone();
+    assert!(permut_dyn(&parts).parse(b"truefalse").is_err());
+    assert_eq!(Ok((b"".as_slice(), b"7".as_slice())), (&shared).parse(b"7"));
+}
+
+#[test]
+fn t_arc_parser() {
+    let p: ArcParser<'static,u8,usize> = ArcParser::new(fmap(seq(is_alpha), <[u8]>::len));
+    let p2 = p.clone();
+    let r = std::thread::spawn(move || (&p2).parse(b"abc1").map(|(_,r)| r).ok()).join().unwrap();
+    assert_eq!(Some(3), r);
+    let v: Vec<Arc<dyn DynParser<u8,usize> + Send + Sync>> = vec![Arc::new(p)];
+    assert_eq!(Ok((b"".as_slice(), 2)), v[0].parse_dyn(b"ab"));
+}
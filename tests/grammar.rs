@@ -0,0 +1,75 @@
+use parcelona::parser_combinators::{*};
+use parcelona::u8::{*};
+use atoi::FromRadix10;
+
+grammar! {
+    ws -> () = [is_space]* => { } ;
+    number -> i64 = d:$([is_dec_digit]+) ws => { i64::from_radix_10(d).0 } ;
+    atom -> i64 = number
+        / "(" ws e:expr ")" ws => { e }
+        / "-" ws a:atom => { -a } ;
+    product -> i64 = a:atom rest:(op:("*" / "/") ws b:atom => { (op, b) })* => {
+        rest.into_iter().fold(a, |x, (op, b)| if op == b"*" { x * b } else { x / b })
+    } ;
+    pub expr -> i64 = a:product rest:(op:("+" / "-") ws b:product => { (op, b) })* => {
+        rest.into_iter().fold(a, |x, (op, b)| if op == b"+" { x + b } else { x - b })
+    } ;
+}
+
+#[test]
+fn t_grammar_expr() {
+    assert_eq!(Ok((b"".as_slice(), 7)), expr(b"1 + 2 * 3"));
+    assert_eq!(Ok((b"".as_slice(), 9)), expr(b"(1 + 2) * 3"));
+    assert_eq!(Ok((b"".as_slice(), -4)), expr(b"-(10 - 6) "));
+    assert_eq!(Ok((b"".as_slice(), 1)), expr(b"8 / 2 / 2 - 1"));
+    assert_eq!(Ok((b")".as_slice(), 5)), expr(b"5)"));
+    assert!(expr(b"*5").is_err());
+}
+
+grammar! {
+    type Input = u8;
+    name -> &'a [u8] = $([is_alpha] [is_alphanum]*) ;
+    value -> &'a [u8] = "\"" v:$((!"\"" .)*) "\"" => { v } ;
+    attr -> (&'a [u8], &'a [u8]) = { seq(is_space) } n:name "=" v:value => { (n, v) } ;
+    pub tag -> (&'a [u8], Vec<(&'a [u8], &'a [u8])>) = "<" n:name a:attr* &(b"/>" / ">") => { (n, a) } ;
+    pub keyword -> bool = "true" !{ any(b"_") } => { true } / "false" => { false } ;
+    pub maybe -> Option<&'a [u8]> = name? ;
+}
+
+#[test]
+fn t_grammar_tag() {
+    let (rest, (n, a)) = tag(br#"<poet author="Byron" date="1809">"#).unwrap();
+    assert_eq!(b">", rest);
+    assert_eq!(b"poet", n);
+    assert_eq!(vec![(b"author".as_slice(), b"Byron".as_slice()), (b"date".as_slice(), b"1809".as_slice())], a);
+    assert!(tag(br#"<poet author="Byron" >"#).is_err());
+
+    assert_eq!(Ok((b" x".as_slice(), true)), keyword(b"true x"));
+    assert!(keyword(b"true_").is_err());
+    assert_eq!(Ok((b"".as_slice(), false)), keyword(b"false"));
+    assert_eq!(Ok((b"1".as_slice(), None)), maybe(b"1"));
+}
+
+#[cfg(feature = "alt_tuple_32")]
+mod wide {
+    use super::*;
+
+    grammar! {
+        pub month -> u8 = "jan" => { 1 } / "feb" => { 2 } / "mar" => { 3 } / "apr" => { 4 } / "may" => { 5 } / "jun" => { 6 }
+            / "jul" => { 7 } / "aug" => { 8 } / "sep" => { 9 } / "oct" => { 10 } / "nov" => { 11 } / "dec" => { 12 }
+            / "1" => { 1 } / "2" => { 2 } / "3" => { 3 } / "4" => { 4 } / "5" => { 5 } / "6" => { 6 } / "7" => { 7 } / "8" => { 8 } ;
+    }
+
+    #[test]
+    fn t_grammar_wide_choice() {
+        assert_eq!(Ok((b"".as_slice(), 12)), month(b"dec"));
+        assert_eq!(Ok((b"".as_slice(), 8)), month(b"8"));
+        assert!(month(b"9").is_err());
+    }
+}
+
+#[cfg(not(any(feature = "alt_tuple_32", feature = "alt_tuple_64")))]
+#[test]
+fn t_grammar_compile_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/grammar_*.rs");
+}
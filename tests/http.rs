@@ -0,0 +1,75 @@
+use parcelona::parser_combinators::{*};
+use parcelona::http::{*};
+
+#[test]
+fn t_http_request() {
+    let data = b"GET /index.html?q=1 HTTP/1.1\r\nHost: example.com\r\nX-Long: a\r\n  b \r\nAccept:*/*\r\n\r\nbody";
+    let (body, r) = request(Limits::default()).parse(data).unwrap();
+    assert_eq!(b"body".as_slice(), body);
+    assert_eq!(b"GET".as_slice(), r.method);
+    assert_eq!(b"/index.html?q=1".as_slice(), r.target);
+    assert_eq!(Version::Http11, r.version);
+    assert_eq!(3, r.headers.len());
+    assert_eq!(Some(b"example.com".as_slice()), r.headers.get("HOST"));
+    assert_eq!(Some(b"*/*".as_slice()), r.headers.get("accept"));
+    assert_eq!(b"a\r\n  b".as_slice(), r.headers.0[1].value);
+    assert_eq!(b"a b".as_slice(), r.headers.0[1].value_unfolded().as_ref());
+
+    let (_, r) = request(Limits::default()).parse(b"CONNECT linkedin.com:443 HTTP/1.0\n\n").unwrap();
+    assert_eq!(Version::Http10, r.version);
+    assert!(r.headers.is_empty());
+}
+
+#[test]
+fn t_http_response() {
+    let data = b"HTTP/1.1 404 Not Found\r\nContent-Length: 3\r\nSet-Cookie: a=1\r\nset-cookie: b=2\r\n\r\nabc";
+    let (body, r) = response(Limits::default()).parse(data).unwrap();
+    assert_eq!((404, b"Not Found".as_slice()), (r.status, r.reason));
+    assert_eq!(Some(3), r.headers.content_length());
+    assert_eq!(2, r.headers.get_all("Set-Cookie").count());
+    assert_eq!(b"abc".as_slice(), body);
+
+    let (_, r) = response(Limits::default()).parse(b"HTTP/1.1 204 \r\n\r\n").unwrap();
+    assert_eq!(b"".as_slice(), r.reason);
+}
+
+#[test]
+fn t_http_errors() {
+    let full = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n";
+    for n in 0..full.len() {
+        let e = request(Limits::default()).parse(&full[..n]).unwrap_err();
+        assert!(e.is_eod(), "{} {:?}", n, e);
+    }
+    let p = request(Limits::default());
+    assert!(!p.parse(b"GET / HTTP/2.0\r\n\r\n").unwrap_err().is_eod());
+    assert!(p.parse(b"GET / HTTP/1.1\r\nHost : a\r\n\r\n").is_err());
+    assert!(p.parse(b"GET / HTTP/1.1\r\nHost: a\x01\r\n\r\n").is_err());
+
+    let e = request(Limits::default().max_headers(1)).parse(b"GET / HTTP/1.1\r\na: 1\r\nb: 2\r\n\r\n").unwrap_err();
+    assert_eq!("too many headers", e.get_msg()[0].as_str());
+    let e = request(Limits::default().max_head_len(16)).parse(b"GET / HTTP/1.1\r\nHost: a").unwrap_err();
+    assert!(!e.is_eod());
+    assert_eq!("message head is too large", e.get_msg()[0].as_str());
+}
+
+#[test]
+fn t_http_chunked() {
+    let data = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip, chunked\r\n\r\n4\r\nWiki\r\n5;ext=1\r\npedia\r\n0\r\nExpires: never\r\n\r\nnext";
+    let (body, r) = response(Limits::default()).parse(data).unwrap();
+    assert!(r.headers.is_chunked());
+    let (rest, c) = chunked(Limits::default()).parse(body).unwrap();
+    assert_eq!(b"next".as_slice(), rest);
+    assert_eq!(b"Wikipedia".to_vec(), c.concat());
+    assert_eq!(Some(b"never".as_slice()), c.trailers.get("expires"));
+    let e = chunked(Limits::default().max_headers(1)).parse(b"0\r\na: 1\r\nb: 2\r\n\r\n").unwrap_err();
+    assert_eq!("too many headers", e.get_msg()[0].as_str());
+    let e = chunked(Limits::default().max_head_len(8)).parse(b"0\r\nExpires: nev").unwrap_err();
+    assert!(!e.is_eod());
+
+    assert!(chunked(Limits::default()).parse(b"4\r\nWi").unwrap_err().is_eod());
+    assert!(chunked(Limits::default()).parse(b"4\r\nWikiX\r\n").is_err());
+    assert!(chunked(Limits::default()).parse(b"ffffffffffffffff\r\n").is_err());
+    let e = chunked(Limits::default()).parse(b"1ffffffffffffffff").unwrap_err();
+    assert!(!e.is_eod());
+    assert_eq!("chunk size is too large", e.get_msg()[0].as_str());
+}
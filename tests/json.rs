@@ -0,0 +1,58 @@
+use parcelona::parser_combinators::{*};
+use parcelona::json::{*};
+use std::borrow::Cow;
+
+#[test]
+fn t_json_value() {
+    let data = br#" {"name": "parcelona", "tags": ["parser", "zero\ncopy"], "ver": -0.4e1, "ok": true, "x": null} "#;
+    let v = parse(data).unwrap();
+    assert_eq!(Some("parcelona"), v.get("name").and_then(Value::as_str));
+    assert_eq!(Some(-4.0), v.get("ver").and_then(Value::as_f64));
+    assert_eq!(Some(true), v.get("ok").and_then(Value::as_bool));
+    assert!(v.get("x").unwrap().is_null());
+    let tags = v.get("tags").and_then(Value::as_array).unwrap();
+    assert!(matches!(tags[0], Value::String(Cow::Borrowed("parser"))));
+    assert!(matches!(&tags[1], Value::String(Cow::Owned(s)) if s == "zero\ncopy"));
+
+    assert_eq!(Ok((b",".as_slice(), "-12.5E+3")), number(b"-12.5E+3,"));
+    assert_eq!(Ok((b"".as_slice(), Cow::Borrowed("\u{1F600} \u{e9}"))), string("\"\u{1F600} \u{e9}\"".as_bytes()));
+    assert_eq!(Ok((b"".as_slice(), Cow::Owned("\u{1F600}/\u{e9}".to_string()))), string(r#""\ud83d\ude00\/é""#.as_bytes()));
+    assert!(string(br#""\ude00""#).is_err());
+    assert!(string(b"\"abc").unwrap_err().is_eod());
+    assert_eq!("control character in string", string(b"\"a\x01\"").unwrap_err().get_msg()[0].as_str());
+
+    let item = between(any(b"("), left(value(8), ws), any(b")"));
+    assert_eq!(Ok((b"".as_slice(), Value::Array(vec![]))), item.parse(b"( [ ] )"));
+}
+
+#[test]
+fn t_json_errors() {
+    let e = parse(b"{\"a\": [1, 2 x]}").unwrap_err();
+    assert_eq!(Some(12), e.offset());
+    assert_eq!(["`,` or `]`"], e.get_expected());
+
+    let deep = |n| { let mut s = "[".repeat(n); s.push_str(&"]".repeat(n)); s };
+    assert!(parse(deep(MAX_DEPTH).as_bytes()).is_ok());
+    let e = parse(deep(MAX_DEPTH+1).as_bytes()).unwrap_err();
+    assert_eq!(Some(MAX_DEPTH), e.offset());
+    assert_eq!(["json nesting is too deep"], e.get_msg());
+    assert!(parse_depth(b"[[1]]", 1).is_err());
+    assert!(parse_depth(b"[1]", 1).is_ok());
+}
+
+#[test]
+fn t_json_test_suite() {
+    // test_parsing of JSONTestSuite, the `y_` and `n_` cases, the `i_` ones are implementation defined
+    let (mut accepted, mut rejected) = (0, 0);
+    for f in std::fs::read_dir("tests/data/json").unwrap() {
+        let path = f.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let data = std::fs::read(&path).unwrap();
+        match &name[..2] {
+            "y_" => { assert!(parse(&data).is_ok(), "{} must be accepted: {:?}", name, parse(&data)); accepted += 1; },
+            "n_" => { assert!(parse(&data).is_err(), "{} must be rejected", name); rejected += 1; },
+            _    => panic!("{} is not a test case", name),
+        }
+    }
+    assert_eq!((95, 188), (accepted, rejected));
+}
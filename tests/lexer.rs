@@ -0,0 +1,75 @@
+use parcelona::parser_combinators::{*};
+use parcelona::lexer::{*};
+use parcelona::report::Report;
+use parcelona::u8::{*};
+use atoi::FromRadix10;
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+enum Kind { If, Ident, Num, Op, LParen, RParen }
+
+fn lexer<'a>() -> Lexer<'a,Kind> {
+    Lexer::new()
+        .skip(seq(is_space))
+        .skip(pair(starts_with(b"#"), seq(|x| *x != b'\n')))
+        .rule(Kind::If, starts_with(b"if"))
+        .rule(Kind::Ident, seq(is_alpha))
+        .rule(Kind::Num, seq(is_dec_digit))
+        .rule(Kind::Op, any(b"+-"))
+        .rule(Kind::LParen, any(b"("))
+        .rule(Kind::RParen, any(b")"))
+}
+
+#[test]
+fn t_tokenize() {
+    let source = b"if iffy 12 # comment\n+x".as_slice();
+    let tokens = lexer().tokenize(source).unwrap();
+    let kinds: Vec<Kind> = tokens.iter().map(|t| t.kind).collect();
+    assert_eq!(vec![Kind::If, Kind::Ident, Kind::Num, Kind::Op, Kind::Ident], kinds);
+    assert_eq!(Token { kind: Kind::Num, text: b"12".as_slice(), offset: 8 }, tokens[2]);
+    assert_eq!(21, tokens[3].offset);
+
+    let e = lexer().tokenize(b"1 + ?").unwrap_err().into_owned(b"1 + ?");
+    assert_eq!(Some(4), e.offset());
+    assert_eq!(["no token rule matches"], e.get_msg());
+}
+
+fn sum<'a>(input: &'a [Token<'a,Kind>]) -> ParseResult<'a,Token<'a,Kind>,i64> {
+    let num = fmap(tok(Kind::Num), |t| i64::from_radix_10(t.text).0);
+    let atom = alt((num, between(tok(Kind::LParen), sum, tok(Kind::RParen))));
+    let tail = more_min(pair(tok(Kind::Op), atom), 0);
+    let (input, (first, rest)) = pair(atom, tail).parse(input)?;
+    let r = rest.into_iter().fold(first, |a, (op, b)| if op.text == b"+" { a+b } else { a-b });
+    Ok((input, r))
+}
+
+#[test]
+fn t_token_parser() {
+    let source = b"1 + (10 - 4) - 2".as_slice();
+    let tokens = lexer().tokenize(source).unwrap();
+    assert_eq!(Ok(5), sum.parse_all(&tokens));
+
+    let tokens = lexer().tokenize(b"if x").unwrap();
+    let p = pair(tok_text(Kind::If, b"if"), tok(Kind::Ident));
+    assert!(p.parse(&tokens).is_ok());
+    assert!(tok_text(Kind::Ident, b"y").parse(&tokens[1..]).is_err());
+}
+
+#[test]
+fn t_token_error() {
+    let source = b"1 +\n(2 x)".as_slice();
+    let tokens = lexer().tokenize(source).unwrap();
+    let p = right(pair(tok(Kind::Num), tok(Kind::Op)), between(tok(Kind::LParen), sum, expected(tok(Kind::RParen), ")")));
+    let e = p.parse(&tokens).unwrap_err().fmt_str();
+    assert!(e.excerpt().contains("Ident"));
+    let e = e.into_source(source);
+    assert_eq!(Some(7), e.offset());
+    assert_eq!("\"x)\"", e.excerpt());
+    assert_eq!([")"], e.get_expected());
+    assert_eq!((2, 4), Report::new(source).line_col(e.offset().unwrap()));
+
+    let e = pair(tok(Kind::Num), pair(tok(Kind::Op), tok(Kind::Num))).parse(&tokens[..2]).unwrap_err().fmt_str();
+    assert!(e.excerpt().starts_with('['));
+    let e = e.into_source(source);
+    assert_eq!(Some(source.len()), e.offset());
+    assert!(e.is_eod());
+}
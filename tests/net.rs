@@ -0,0 +1,78 @@
+use parcelona::net::{*};
+use std::net::{IpAddr,Ipv4Addr,Ipv6Addr};
+
+fn v6(x: &str) -> Ipv6Addr { x.parse().unwrap() }
+
+#[test]
+fn t_net_ipv4() {
+    assert_eq!(Ok((&b":80"[..], Ipv4Addr::new(10, 0, 0, 1))), ipv4(b"10.0.0.1:80"));
+    assert_eq!(Ipv4Addr::new(255, 255, 255, 0), ipv4(b"255.255.255.0").unwrap().1);
+    for x in ["256.1.1.1", "1.2.3", "01.2.3.4", "1.2.3.1000", "1..2.3", "a.b.c.d", ""] {
+        assert!(ipv4(x.as_bytes()).is_err(), "{x}");
+    }
+}
+
+#[test]
+fn t_net_ipv6() {
+    // RFC 4291 2.2 and RFC 5952 forms
+    for x in [
+        "2001:db8:0:0:8:800:200c:417a", "2001:DB8::8:800:200C:417A", "ff01::101", "::1", "::", "1::",
+        "1:2:3:4:5:6:7::", "::2:3:4:5:6:7:8", "0:0:0:0:0:0:13.1.68.3", "::13.1.68.3",
+        "::ffff:129.144.52.38", "64:ff9b::192.0.2.33", "1:2:3:4:5:6:1.2.3.4",
+    ] {
+        assert_eq!(Ok((&b""[..], v6(x))), ipv6(x.as_bytes()), "{x}");
+    }
+    for x in [
+        "1::2::3", ":1::", "12345::", "1:2:3:4:5:6:7", "::1.2.3", "1:2:3:4:5:6:7:1.2.3.4",
+        "::1:2:3:4:5:6:7:8", "g::",
+    ] {
+        assert!(ipv6(x.as_bytes()).is_err(), "{x}");
+    }
+    assert_eq!(Ok((&b":9"[..], v6("1:2:3:4:5:6:7:8"))), ipv6(b"1:2:3:4:5:6:7:8:9"));
+    // the address ends before `]`, `:` not followed by a digit, or space
+    assert_eq!(Ok((&b"]:443"[..], v6("::1"))), ipv6(b"::1]:443"));
+    assert_eq!(Ok((&b": up"[..], v6("fe80::1"))), ipv6(b"fe80::1: up"));
+    assert_eq!(Ok((&b" x"[..], (v6("fe80::1"), Some(&b"eth0"[..])))), ipv6_zone(b"fe80::1%eth0 x"));
+    assert_eq!(Ok((&b"%"[..], (v6("fe80::1"), None))), ipv6_zone(b"fe80::1%"));
+}
+
+#[test]
+fn t_net_ip_cidr() {
+    assert_eq!(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), ip(b"1.2.3.4").unwrap().1);
+    assert_eq!(IpAddr::V6(v6("1::2")), ip(b"1::2").unwrap().1);
+    assert!(ip(b"x").is_err());
+
+    let (rest, c) = cidr(b"192.168.10.7/16 allow").unwrap();
+    assert_eq!(b" allow", rest);
+    assert_eq!(16, c.prefix);
+    assert_eq!("192.168.0.0".parse::<IpAddr>().unwrap(), c.network());
+    assert!(c.contains(&"192.168.255.1".parse().unwrap()));
+    assert!(!c.contains(&"192.169.0.1".parse().unwrap()));
+    assert!(!c.contains(&"::1".parse().unwrap()));
+    assert_eq!("192.168.10.7/16", c.to_string());
+
+    let c = cidr(b"2001:db8::/32").unwrap().1;
+    assert!(c.contains(&"2001:db8:ffff::1".parse().unwrap()));
+    assert!(!c.contains(&"2001:db9::1".parse().unwrap()));
+    assert!(cidr(b"0.0.0.0/0").unwrap().1.contains(&"8.8.8.8".parse().unwrap()));
+    assert!(cidr(b"1.2.3.4/32").unwrap().1.contains(&"1.2.3.4".parse().unwrap()));
+    let c = Cidr { addr: "1.2.3.4".parse().unwrap(), prefix: 40 };
+    assert_eq!("1.2.3.4".parse::<IpAddr>().unwrap(), c.network());
+    assert!(!c.contains(&"1.2.3.5".parse().unwrap()));
+    assert!(Cidr { addr: "::1".parse().unwrap(), prefix: 200 }.contains(&"::1".parse().unwrap()));
+    for x in ["1.2.3.4/33", "1.2.3.4", "1.2.3.4/", "::/129", "1.2.3.4/08"] {
+        assert!(cidr(x.as_bytes()).is_err(), "{x}");
+    }
+}
+
+#[test]
+fn t_net_mac() {
+    let m = MacAddr([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+    assert_eq!(Ok((&b""[..], m)), mac(b"00:1a:2b:3c:4d:5e"));
+    assert_eq!(Ok((&b" up"[..], m)), mac(b"00-1A-2B-3C-4D-5E up"));
+    assert_eq!(Ok((&b""[..], m)), mac(b"001a.2b3c.4d5e"));
+    assert_eq!("00:1a:2b:3c:4d:5e", m.to_string());
+    for x in ["00:1a:2b:3c:4d", "00:1a-2b:3c:4d:5e", "00.1a.2b.3c.4d.5e", "001a2b3c4d5e", "00:1a:2b:3c:4d:5e6"] {
+        assert!(mac(x.as_bytes()).is_err(), "{x}");
+    }
+}
@@ -0,0 +1,91 @@
+use parcelona::parser_combinators::{*};
+use parcelona::packrat::{*};
+use parcelona::u8::{*};
+use atoi::FromRadix10;
+use std::cell::Cell;
+
+#[test]
+fn t_memo_backtracking() {
+    let calls = Cell::new(0_usize);
+    let word = |i| { calls.set(calls.get()+1); seq(is_alpha).parse(i) };
+    let table = MemoTable::new();
+    let w = memo(word, &table);
+    let p = alt((left(w, any(b",")), left(w, any(b".")), left(w, any(b";"))));
+
+    assert_eq!(Ok((b"".as_slice(), b"abc".as_slice())), p.parse(b"abc;"));
+    assert_eq!(1, calls.get());
+    assert_eq!(1, table.len());
+
+    let data = b"abc;".to_vec();
+    let tables: [&dyn MemoClear; 1] = [&table];
+    let scoped = memo_scope(p, &tables);
+    assert_eq!(Ok((b"".as_slice(), b"abc".as_slice())), scoped.parse(&data));
+    assert_eq!(2, calls.get());
+    assert!(table.is_empty());
+}
+
+struct Tables<'a> {
+    sum: MemoTable<'a,u8,i64>,
+    atom: MemoTable<'a,u8,i64>,
+}
+
+fn atom<'a,'t>(t:&'t Tables<'a>) -> impl Parser<'a,u8,i64>+'t {
+    let number = fmap(seq(is_dec_digit), |x| i64::from_radix_10(x).0);
+    memo(move |i| alt((number, between(any(b"("), sum(t), any(b")")))).parse(i), &t.atom)
+}
+
+fn sum<'a,'t>(t:&'t Tables<'a>) -> impl Parser<'a,u8,i64>+'t {
+    let plus = fmap(sep_pair(atom(t), any(b"+"), atom(t)), |(a,b)| a+b);
+    let minus = fmap(sep_pair(atom(t), any(b"-"), atom(t)), |(a,b)| a-b);
+    memo(move |i| alt((plus, minus, atom(t))).parse(i), &t.sum)
+}
+
+#[test]
+fn t_memo_recursive() {
+    let t = Tables { sum: MemoTable::new(), atom: MemoTable::new() };
+    assert_eq!(Ok((b"".as_slice(), 2)), sum(&t).parse(b"(1+(5-3))-1"));
+    let data = b"((((((((((7))))))))))".to_vec();
+    let tables: [&dyn MemoClear; 2] = [&t.sum, &t.atom];
+    assert_eq!(Ok((b"".as_slice(), 7)), memo_scope(sum(&t), &tables).parse(&data));
+}
+
+fn num<'a>() -> impl Parser<'a,u8,i64> {
+    fmap(seq(is_dec_digit), |x| i64::from_radix_10(x).0)
+}
+
+// expr := expr "-" num / num
+fn expr<'a,'t>(t:&'t MemoTable<'a,u8,i64>) -> impl Parser<'a,u8,i64>+'t {
+    lr(move |i| alt((fmap(sep_pair(expr(t), any(b"-"), num()), |(a,b)| a-b), num())).parse(i), t)
+}
+
+#[test]
+fn t_lr_direct() {
+    let t = MemoTable::new();
+    assert_eq!(Ok((b"".as_slice(), 1)), expr(&t).parse(b"5-3-1"));
+    let data = b"10-1-2-3;".to_vec();
+    let tables: [&dyn MemoClear; 1] = [&t];
+    assert_eq!(Ok((b";".as_slice(), 4)), memo_scope(expr(&t), &tables).parse(&data));
+    assert_eq!(Ok((b"-".as_slice(), 7)), memo_scope(expr(&t), &tables).parse(b"7-"));
+    assert!(memo_scope(expr(&t), &tables).parse(b"-7").is_err());
+}
+
+// list := items / num
+// items := list "," num
+fn list<'a,'t>(t:&'t MemoTable<'a,u8,Vec<i64>>) -> impl Parser<'a,u8,Vec<i64>>+'t {
+    let one = fmap(num(), |x| vec![x]);
+    lr(move |i| alt((items(t), one)).parse(i), t)
+}
+
+fn items<'a,'t>(t:&'t MemoTable<'a,u8,Vec<i64>>) -> impl Parser<'a,u8,Vec<i64>>+'t {
+    fmap(sep_pair(move |i| list(t).parse(i), any(b","), num()), |(mut a,b)| { a.push(b); a })
+}
+
+#[test]
+fn t_lr_indirect() {
+    let t = MemoTable::new();
+    assert_eq!(Ok((b"".as_slice(), vec![1,22,3])), list(&t).parse(b"1,22,3"));
+    let data = b"4,5,".to_vec();
+    let tables: [&dyn MemoClear; 1] = [&t];
+    assert_eq!(Ok((b",".as_slice(), vec![4,5])), memo_scope(list(&t), &tables).parse(&data));
+    assert!(memo_scope(items(&t), &tables).parse(b"4").is_err());
+}
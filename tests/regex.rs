@@ -0,0 +1,35 @@
+#![cfg(feature = "regex")]
+use parcelona::parser_combinators::{*};
+use parcelona::regex::{*};
+use parcelona::u8::{*};
+
+#[test]
+fn t_regex() {
+    let uuid = regex(r"[0-9a-f]{8}-([0-9a-f]{4}-){3}[0-9a-f]{12}").unwrap();
+    let data = b"id=123e4567-e89b-12d3-a456-426614174000;".as_slice();
+    assert_eq!(Ok((b";".as_slice(), b"123e4567-e89b-12d3-a456-426614174000".as_slice())),
+        right(starts_with(b"id="), &uuid).parse(data));
+
+    // anchored at the start, no search further in the input
+    let num = regex(r"\d+").unwrap();
+    assert!((&num).parse(b"x12").is_err());
+    assert_eq!(Ok((b"".as_slice(), vec![b"1".as_slice(), b"22".as_slice()])),
+        sep_list(&num, seq(is_space), &num).parse(b"1 22"));
+
+    let e = (&num).parse(b"").unwrap_err();
+    assert!(e.is_eod());
+    let e = (&num).parse(b"ab").unwrap_err();
+    assert!(!e.is_eod());
+    assert_eq!(r"/\d+/", e.get_expected()[0].as_str());
+    assert!(regex(r"(").is_err());
+}
+
+#[test]
+fn t_regex_captures() {
+    let version = regex(r"v(\d+)\.(\d+)(?:\.(\d+))?").unwrap();
+    let (rest, c) = version.captures().parse(b"v1.22 beta").unwrap();
+    assert_eq!(b" beta".as_slice(), rest);
+    assert_eq!(vec![Some(b"v1.22".as_slice()), Some(b"1".as_slice()), Some(b"22".as_slice()), None], c);
+    let p = fmap(version.captures(), |c| c.len());
+    assert_eq!(Ok((b"".as_slice(), 4)), p.parse(b"v0.1.2"));
+}
@@ -0,0 +1,41 @@
+use parcelona::parser_combinators::{*};
+use parcelona::report::Report;
+use parcelona::u8::{*};
+
+#[test]
+fn t_report() {
+    let source: &[u8] = b"a = 1\nb = x2\n";
+    let value = seq(is_dec_digit).expected("number").msg_err("value parse error");
+    let line = left(sep_pair(seq(is_alpha), starts_with(b" = "), value), any(b"\n")).msg_err("line parse error");
+    let e = line.more_exact(2).parse_all(source).unwrap_err();
+
+    let r = Report::new(source).name("conf.ini").render(&e);
+    assert_eq!(r, "\
+error: line parse error
+ --> conf.ini:2:5
+  |
+2 | b = x2
+  |     ^^
+  = note: value parse error
+  = expected: number
+");
+    assert!(Report::new(source).color(true).render(&e).contains("\x1b[1;31m^^\x1b[0m"));
+}
+
+#[test]
+fn t_report_eod() {
+    let source: &[u8] = b"key\r\nvalue";
+    let p = pair(seq(is_alpha), right(seq(is_eol), starts_with(b"values")));
+    let e = p.parse(source).unwrap_err().into();
+
+    let r = Report::new(source).render(&e);
+    assert_eq!(r, "\
+error: parse error
+ --> 2:1
+  |
+2 | value
+  | ^
+  = note: unexpected end of data
+");
+    assert_eq!((2, 6), Report::new(source).line_col(source.len()));
+}
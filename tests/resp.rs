@@ -0,0 +1,110 @@
+use parcelona::parser_combinators::{*};
+use parcelona::resp::{*};
+use std::borrow::Cow;
+
+fn v(input: &[u8]) -> Value<'_> {
+    let (rest, v) = value(Limits::default()).parse(input).unwrap();
+    assert!(rest.is_empty(), "{rest:?}");
+    v
+}
+
+fn bulk(x: &[u8]) -> Value<'_> { Value::BulkString(Cow::Borrowed(x)) }
+
+#[test]
+fn t_resp2() {
+    assert_eq!(Value::SimpleString(b"OK"), v(b"+OK\r\n"));
+    assert_eq!(Value::SimpleString(b""), v(b"+\r\n"));
+    assert_eq!(Value::Error(b"ERR unknown command 'x'"), v(b"-ERR unknown command 'x'\r\n"));
+    assert_eq!(Value::Integer(-42), v(b":-42\r\n"));
+    assert_eq!(Value::Integer(1000), v(b":+1000\r\n"));
+    assert_eq!(bulk(b"hel\r\nlo"), v(b"$7\r\nhel\r\nlo\r\n"));
+    assert_eq!(bulk(b""), v(b"$0\r\n\r\n"));
+    assert_eq!(Value::Null, v(b"$-1\r\n"));
+    assert_eq!(Value::Null, v(b"*-1\r\n"));
+    assert_eq!(Value::Array(vec![]), v(b"*0\r\n"));
+    let cmd = v(b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n");
+    assert_eq!(Some(&b"SET"[..]), cmd.as_array().unwrap()[0].as_bytes());
+    assert_eq!(
+        Value::Array(vec![Value::Array(vec![Value::Integer(1), Value::Null]), Value::SimpleString(b"x")]),
+        v(b"*2\r\n*2\r\n:1\r\n$-1\r\n+x\r\n"),
+    );
+}
+
+#[test]
+fn t_resp3() {
+    assert_eq!(Value::Null, v(b"_\r\n"));
+    assert_eq!(Value::Boolean(true), v(b"#t\r\n"));
+    assert_eq!(Value::Double(1.5e3), v(b",1.5e3\r\n"));
+    assert_eq!(Value::Double(f64::NEG_INFINITY), v(b",-inf\r\n"));
+    assert!(matches!(v(b",nan\r\n"), Value::Double(x) if x.is_nan()));
+    assert_eq!(Value::BigNumber(b"-3492890328409238509324850943850943825024385"), v(b"(-3492890328409238509324850943850943825024385\r\n"));
+    assert_eq!(Value::BulkError(b"SYNTAX invalid syntax"), v(b"!21\r\nSYNTAX invalid syntax\r\n"));
+    assert_eq!(Value::Verbatim { format: b"txt", text: b"Some string" }, v(b"=15\r\ntxt:Some string\r\n"));
+    assert_eq!(
+        Value::Map(vec![(Value::SimpleString(b"first"), Value::Integer(1)), (Value::SimpleString(b"second"), Value::Integer(2))]),
+        v(b"%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n"),
+    );
+    assert_eq!(Value::Set(vec![Value::Integer(1), bulk(b"a")]), v(b"~2\r\n:1\r\n$1\r\na\r\n"));
+    let push = v(b">3\r\n$7\r\nmessage\r\n$2\r\nch\r\n$2\r\nhi\r\n");
+    assert!(matches!(push, Value::Push(ref x) if x.len() == 3));
+    assert_eq!(
+        Value::Attribute {
+            attrs: vec![(Value::SimpleString(b"ttl"), Value::Integer(3600))],
+            value: Box::new(Value::Array(vec![Value::Integer(2), Value::Integer(3)])),
+        },
+        v(b"|1\r\n+ttl\r\n:3600\r\n*2\r\n:2\r\n:3\r\n"),
+    );
+    // streamed string and aggregates
+    assert_eq!(Value::BulkString(Cow::Owned(b"Hello world".to_vec())), v(b"$?\r\n;4\r\nHell\r\n;6\r\no worl\r\n;1\r\nd\r\n;0\r\n"));
+    assert_eq!(Value::Array(vec![Value::Integer(1), Value::Integer(2)]), v(b"*?\r\n:1\r\n:2\r\n.\r\n"));
+    assert_eq!(Value::Map(vec![(Value::SimpleString(b"a"), Value::Integer(1))]), v(b"%?\r\n+a\r\n:1\r\n.\r\n"));
+}
+
+#[test]
+fn t_resp_incomplete() {
+    let data: &[u8] = b"*2\r\n$3\r\nGET\r\n%1\r\n+k\r\n$?\r\n;2\r\nab\r\n;0\r\n";
+    assert!(value(Limits::default()).parse(data).is_ok());
+    for n in 0..data.len() {
+        let e = value(Limits::default()).parse(&data[..n]).unwrap_err();
+        assert!(e.is_eod(), "{n}");
+    }
+}
+
+#[test]
+fn t_resp_errors() {
+    for x in [
+        &b"?x\r\n"[..], b":12a\r\n", b":\r\n", b"+OK\n", b"+O\rK\r\n", b"$3\r\nabcd\r\n", b"$-2\r\n", b"#x\r\n",
+        b",abc\r\n", b"(12.5\r\n", b"=3\r\ntxt\r\n", b"!-1\r\n", b"%-1\r\n", b"%?\r\n+a\r\n.\r\n", b"_x\r\n",
+        b":99999999999999999999\r\n", b"$?\r\nab\r\n",
+    ] {
+        let e = value(Limits::default()).parse(x).unwrap_err();
+        assert!(!e.is_eod(), "{x:?}");
+    }
+    let limits = Limits::default().max_bulk_len(4).max_line_len(8).max_depth(2);
+    assert!(value(limits).parse(b"$4\r\nabcd\r\n").is_ok());
+    assert!(!value(limits).parse(b"$5\r\nabcde\r\n").unwrap_err().is_eod());
+    assert!(value(limits).parse(b"$?\r\n;2\r\nab\r\n;2\r\ncd\r\n;0\r\n").is_ok());
+    let e = value(limits).parse(b"$?\r\n;2\r\nab\r\n;2\r\ncd\r\n;1\r\ne\r\n;0\r\n").unwrap_err();
+    assert!(!e.is_eod());
+    assert_eq!("length is out of range", e.get_msg()[0].as_str());
+    assert!(!value(limits).parse(b"+123456789").unwrap_err().is_eod());
+    assert!(value(limits).parse(b"*1\r\n*1\r\n:1\r\n").is_ok());
+    assert!(!value(limits).parse(b"*1\r\n*1\r\n*1\r\n:1\r\n").unwrap_err().is_eod());
+}
+
+#[test]
+fn t_resp_pipeline() {
+    let data: &[u8] = b"+OK\r\n:1\r\n*1\r\n$4\r\nPING\r\n$5\r\nhel";
+    let mut p = pipeline(Limits::default(), data);
+    let r: Vec<_> = (&mut p).map(|x| x.unwrap()).collect();
+    assert_eq!(vec![Value::SimpleString(b"OK"), Value::Integer(1), Value::Array(vec![bulk(b"PING")])], r);
+    assert_eq!(b"$5\r\nhel", p.remaining());
+
+    let mut p = pipeline(Limits::default(), b"+OK\r\n?bad\r\n+next\r\n");
+    assert!(p.next().unwrap().is_ok());
+    assert!(p.next().unwrap().is_err());
+    assert!(p.next().is_none());
+
+    let (rest, raw) = frame(Limits::default()).parse(b"*1\r\n:5\r\n+next\r\n").unwrap();
+    assert_eq!((&b"*1\r\n:5\r\n"[..], &b"+next\r\n"[..]), (raw, rest));
+}
@@ -307,6 +307,20 @@ fn t_classo1() {
 }
 
 
+#[test]
+fn t_classo_end() { 
+    let mut name: ClassOfSymbols<u8> = Default::default();
+    name.range_enable_push(&[ALPHA_LOWER, ALPHA_UPPER, DEC_DIGIT])
+        .one_enable_push(&[45,46,95]) // - . _
+        .default_enable_one(false);
+
+    assert_eq!((b"".as_slice(), b"ab1".as_slice()), (&name).parse(b"ab1").unwrap());
+    let mut word: ClassOfSymbols<u8> = Default::default();
+    word.parts_enable_push(&[b"ab"]).range_enable_push(&[DEC_DIGIT]);
+    assert_eq!((b"".as_slice(), b"ab".as_slice()), (&word).parse(b"ab").unwrap());
+}
+
+
 #[test]
 fn t_ffun() { 
     fn ft(input: &[u8]) -> ParseResult<u8,&[u8]> {
@@ -332,3 +346,154 @@ fn t_ffun_alt() {
     let r = p.parse(input).unwrap();
     assert_eq!((b"truefalse".as_slice(), b"test".as_slice()), r);
 }
+
+#[test]
+fn t_flat_map() {
+    let data: &[u8] = b"3abcde";
+    let p = flat_map(fmap(seq_exact(is_dec_digit,1), |x| (x[0]-b'0') as usize), take);
+    assert_eq!(Ok((b"de".as_slice(), b"abc".as_slice())), p.parse(data));
+}
+
+#[test]
+fn t_length_value() {
+    let data: &[u8] = &[0, 4, 0, 2, 8, 9, 1];
+    let p = length_value(take_len_be_u16, take_record_be_u16);
+    assert_eq!(Ok(([1_u8].as_slice(), [8_u8,9].as_slice())), p.parse(data));
+
+    let data: &[u8] = &[0, 5, 0, 2, 8, 9, 1];
+    assert!(p.parse(data).is_err());
+
+    let data: &[u8] = &[0, 5, 0, 2, 8];
+    assert!(p.parse(data).unwrap_err().is_eod());
+
+    // errors point into the whole source, not into the record
+    let data: &[u8] = &[0, 5, 0, 2, 8, 9, 1, 6, 6, 6];
+    assert_eq!(6, data.len() - p.parse(data).unwrap_err().get_input().len());
+    let data: &[u8] = &[0, 3, 0, 2, 8, 6, 6, 6];
+    assert_eq!(4, data.len() - p.parse(data).unwrap_err().get_input().len());
+}
+
+#[test]
+fn t_length_count() {
+    let data: &[u8] = &[2, 1, 7, 2, 8, 9, 5];
+    let p = length_count(take_len_be_u8, take_record_be_u8);
+    assert_eq!(Ok(([5_u8].as_slice(), vec![[7_u8].as_slice(), [8_u8,9].as_slice()])), p.parse(data));
+
+    let data: &[u8] = &[3, 1, 7, 2, 8, 9];
+    assert!(p.parse(data).is_err());
+}
+
+#[test]
+fn t_parse_all() {
+    let p = seq(is_alpha);
+    assert_eq!(Ok(b"abc".as_slice()), p.parse_all(b"abc"));
+
+    let e = p.parse_all(b"abc12").unwrap_err();
+    assert_eq!(Some(3), e.offset());
+    assert_eq!(2, e.remaining());
+    assert!(!e.is_eod());
+
+    assert_eq!(Ok(b"abc".as_slice()), p.parse_complete(b"abc \r\n", is_space));
+    assert!(p.parse_complete(b"abc 1", is_space).is_err());
+
+    let e = p.parse_all(b"").unwrap_err();
+    assert!(e.is_eod());
+    assert_eq!(Some(0), e.offset());
+}
+
+#[test]
+fn t_parse_all_send() {
+    fn parse_owned(data: &[u8]) -> Result<Vec<u8>, ParseError> {
+        let r = right(starts_with(b"#"), seq(is_hex_digit)).strerr().parse_all(data)?;
+        Ok(r.to_vec())
+    }
+    let e = std::thread::spawn(|| parse_owned(b"#2F14DZ")).join().unwrap().unwrap_err();
+    assert_eq!(Some(6), e.offset());
+    assert_eq!(["unexpected trailing data".to_string()].as_slice(), e.get_msg());
+    assert_eq!(b"2F14DF".to_vec(), parse_owned(b"#2F14DF").unwrap());
+}
+
+#[test]
+fn t_parse_error_owned() {
+    fn is_static_error<E: std::error::Error + Send + Sync + 'static>(_: &E) {}
+
+    let data: &[u8] = b"#2F14";
+    let p = right(starts_with(b"#").expected("#"), seq_exact(is_hex_digit,6).expected("6 hex digits"))
+        .msg_err("color parse error")
+        .strerr();
+    let e: ParseError = p.parse(data).unwrap_err().into();
+    is_static_error(&e);
+    assert_eq!(None, e.offset());
+    assert!(e.is_eod());
+    assert_eq!(["6 hex digits".to_string()].as_slice(), e.get_expected());
+    assert_eq!(["color parse error".to_string()].as_slice(), e.get_msg());
+    assert_eq!("\"2F14\"", e.excerpt());
+
+    let e = p.parse(data).unwrap_err().into_owned(data);
+    assert_eq!(Some(1), e.offset());
+    assert_eq!("Err at 1: \"2F14\"\ncolor parse error\nexpected: 6 hex digits\n", e.to_string());
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(e);
+    assert!(boxed.to_string().starts_with("Err at 1"));
+}
+
+#[test]
+fn t_tuple() {
+    let data: &[u8] = b"key = 12;rest";
+    let space = seq(is_space);
+    let p = (seq(is_alpha), space, starts_with(b"="), space, seq(is_dec_digit), any(b";")).tuple();
+    let (rest, (k, _, eq, _, v, end)) = p.parse(data).unwrap();
+    assert_eq!(b"rest", rest);
+    assert_eq!((b"key".as_slice(), b"=".as_slice(), b"12".as_slice(), b";".as_slice()), (k, eq, v, end));
+
+    let p = tuple((seq(is_alpha), space, starts_with(b"+")));
+    assert!(p.parse(data).is_err());
+}
+
+#[test]
+fn t_seq_select() {
+    let data: &[u8] = b"key = 12;rest";
+    let space = seq(is_space);
+    let p = parcelona::seq_select!((seq(is_alpha), space, starts_with(b"="), space, seq(is_dec_digit), any(b";")); 0, 4);
+    assert_eq!(Ok((b"rest".as_slice(), (b"key".as_slice(), b"12".as_slice()))), p.parse(data));
+
+    let p = parcelona::seq_select!((space, seq(is_dec_digit)); 1);
+    assert_eq!(Ok((b";rest".as_slice(), b"12".as_slice())), p.parse(b" 12;rest"));
+}
+
+#[test]
+fn t_parse_iter() {
+    let data="b:12 b:2 jkmn CONNECT: 1 mnbnm mnmn/r/n nbn".as_bytes();
+    let mut it = find(seq(is_dec_digit)).iter(data);
+    let r: Vec<&[u8]> = it.by_ref().map_while(Result::ok).collect();
+    assert_eq!(vec!["12".as_bytes(), "2".as_bytes(), "1".as_bytes()], r);
+    assert_eq!(" mnbnm mnmn/r/n nbn".as_bytes(), it.remaining());
+    assert!(it.next().is_none());
+
+    let data = b"k=1;k=2;k=x;k=4;".as_slice();
+    let rec = between(starts_with(b"k="), seq(is_dec_digit), starts_with(b";"));
+    let mut it = parse_iter(rec, data);
+    assert_eq!(Some(Ok(b"1".as_slice())), it.next());
+    assert_eq!(Some(Ok(b"2".as_slice())), it.next());
+    assert!(matches!(it.next(), Some(Err(_))));
+    assert!(it.next().is_none());
+    assert_eq!(b"k=x;k=4;".as_slice(), it.remaining());
+
+    let mut it = rec.iter(b"k=1;");
+    assert_eq!(1, it.by_ref().count());
+    assert!(it.remaining().is_empty());
+
+    let mut it = option(rec).iter(b"z");
+    assert_eq!(Some(Ok(None)), it.next());
+    assert_eq!("parser made no progress", it.next().unwrap().unwrap_err().get_msg()[0].as_str());
+    assert!(it.next().is_none());
+    assert_eq!(b"z".as_slice(), it.remaining());
+}
+
+#[test]
+fn t_take_record_be_u8() {
+    assert_eq!(Ok((b"x".as_slice(), b"".as_slice())), take_record_be_u8(b"\x00x"));
+    assert_eq!(Ok((b"".as_slice(), b"ab".as_slice())), take_record_be_u8(b"\x02ab"));
+    assert!(take_record_be_u8(b"\x03ab").unwrap_err().is_eod());
+    assert!(take_record_be_u8(b"").unwrap_err().is_eod());
+}
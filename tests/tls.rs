@@ -0,0 +1,107 @@
+use parcelona::tls::{*};
+use std::borrow::Cow;
+
+fn u16b(x: usize) -> Vec<u8> { vec![(x >> 8) as u8, x as u8] }
+fn u24b(x: usize) -> Vec<u8> { vec![(x >> 16) as u8, (x >> 8) as u8, x as u8] }
+fn ext(typ: u16, data: &[u8]) -> Vec<u8> { [u16b(typ as usize), u16b(data.len()), data.to_vec()].concat() }
+fn vec16(data: &[u8]) -> Vec<u8> { [u16b(data.len()), data.to_vec()].concat() }
+fn vec8(data: &[u8]) -> Vec<u8> { [vec![data.len() as u8], data.to_vec()].concat() }
+
+fn handshake_msg(typ: u8, body: &[u8]) -> Vec<u8> { [vec![typ], u24b(body.len()), body.to_vec()].concat() }
+fn record_of(typ: u8, data: &[u8]) -> Vec<u8> { [vec![typ, 3, 1], u16b(data.len()), data.to_vec()].concat() }
+
+fn client_hello_body() -> Vec<u8> {
+    let sni = vec16(&[vec![0], vec16(b"example.com")].concat());
+    let alpn = vec16(&[vec8(b"h2"), vec8(b"http/1.1")].concat());
+    let versions = vec8(&[0x3a, 0x3a, 3, 4, 3, 3]);
+    let shares = vec16(&[u16b(0x2a2a), vec16(&[0]), u16b(0x001d), vec16(&[7; 32])].concat());
+    let exts = [
+        ext(0x1a1a, &[]), ext(EXT_SERVER_NAME, &sni), ext(EXT_ALPN, &alpn),
+        ext(EXT_SUPPORTED_VERSIONS, &versions), ext(EXT_KEY_SHARE, &shares), ext(0xff01, &[0]),
+    ].concat();
+    [
+        vec![3, 3], vec![1; 32], vec8(&[2; 32]),
+        vec16(&[0x0a, 0x0a, 0x13, 0x01, 0x13, 0x02]), vec8(&[0]), vec16(&exts),
+    ].concat()
+}
+
+#[test]
+fn t_tls_client_hello() {
+    let data = [record_of(HANDSHAKE, &handshake_msg(CLIENT_HELLO, &client_hello_body())), b"next".to_vec()].concat();
+    let (rest, h) = client_hello_record(&data).unwrap();
+    assert_eq!(b"next".as_slice(), rest);
+    assert_eq!(0x0303, h.legacy_version);
+    assert_eq!(32, h.session_id.len());
+    assert_eq!(Some(b"example.com".as_slice()), h.sni());
+    assert_eq!(Some([b"h2".as_slice(), b"http/1.1"].as_slice()), h.alpn());
+    assert_eq!(vec![0x0304, 0x0303], h.supported_versions());
+    assert_eq!(vec![0x1301, 0x1302], h.cipher_suites());
+    assert_eq!(3, h.cipher_suites.len());
+    let shares = h.key_shares();
+    assert_eq!(1, shares.len());
+    assert_eq!((0x001d, 32), (shares[0].group, shares[0].key_exchange.len()));
+    assert_eq!(Some(&Extension::Unknown { typ: 0x1a1a, data: &[] }), h.extensions.first());
+    assert!(is_grease(0xfafa) && !is_grease(0x1301) && !is_grease(0x0a1a));
+}
+
+#[test]
+fn t_tls_partial_and_malformed() {
+    let data = record_of(HANDSHAKE, &handshake_msg(CLIENT_HELLO, &client_hello_body()));
+    for n in 0..data.len() {
+        assert!(client_hello_record(&data[..n]).unwrap_err().is_eod(), "{}", n);
+    }
+    // extension list longer than the complete record
+    let mut body = client_hello_body();
+    let n = 2 + 32 + 33 + 8 + 2;
+    body[n] += 1;
+    let data = record_of(HANDSHAKE, &handshake_msg(CLIENT_HELLO, &body));
+    let e = client_hello_record(&data).unwrap_err();
+    assert!(!e.is_eod());
+    assert_eq!("malformed ClientHello extensions", e.get_msg().last().unwrap().as_str());
+    // the same body alone may be truncated
+    let e = client_hello(&body).unwrap_err();
+    assert!(e.is_eod());
+    assert_eq!("malformed ClientHello extensions", e.get_msg().last().unwrap().as_str());
+
+    assert!(!record(&[22, 3, 1, 0xff, 0xff]).unwrap_err().is_eod());
+    assert!(!record(&[99, 3, 1, 0, 0]).unwrap_err().is_eod());
+    assert!(client_hello_record(&record_of(APPLICATION_DATA, &[1, 2])).is_err());
+}
+
+#[test]
+fn t_tls_server_hello() {
+    let exts = [ext(EXT_SUPPORTED_VERSIONS, &[3, 4]), ext(EXT_KEY_SHARE, &[u16b(0x001d), vec16(&[9; 32])].concat())].concat();
+    let body = [vec![3, 3], vec![5; 32], vec8(&[]), vec![0x13, 0x01], vec![0], vec16(&exts)].concat();
+    let msg = handshake_msg(SERVER_HELLO, &body);
+    let (_, h) = handshake(&msg).unwrap();
+    let (_, s) = server_hello(h.body).unwrap();
+    assert_eq!(0x0304, s.version());
+    assert_eq!(0x1301, s.cipher_suite);
+    assert!(!s.is_hello_retry_request());
+    assert!(matches!(&s.extensions[1], Extension::KeyShare(v) if v[0].key_exchange.len() == 32));
+
+    let exts = [ext(EXT_SUPPORTED_VERSIONS, &[3, 4]), ext(EXT_KEY_SHARE, &u16b(0x0017))].concat();
+    let body = [vec![3, 3], HELLO_RETRY_REQUEST.to_vec(), vec8(&[]), vec![0x13, 0x01], vec![0], vec16(&exts)].concat();
+    let (_, s) = server_hello(&body).unwrap();
+    assert!(s.is_hello_retry_request());
+    assert_eq!(Extension::KeyShare(vec![KeyShare { group: 0x0017, key_exchange: &[] }]), s.extensions[1]);
+
+    // no extensions in old hello
+    let body = [vec![3, 1], vec![5; 32], vec8(&[]), vec![0, 0x2f], vec![0]].concat();
+    assert!(server_hello(&body).unwrap().1.extensions.is_empty());
+}
+
+#[test]
+fn t_tls_reassemble() {
+    let msg = handshake_msg(CLIENT_HELLO, &client_hello_body());
+    let one = record_of(HANDSHAKE, &msg);
+    assert!(matches!(handshake_reassemble(&one).unwrap().1, Cow::Borrowed(_)));
+
+    let data = [record_of(HANDSHAKE, &msg[..3]), record_of(HANDSHAKE, &msg[3..100]), record_of(HANDSHAKE, &msg[100..])].concat();
+    let (rest, m) = handshake_reassemble(&data).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(msg, m.as_ref());
+    let (_, h) = handshake(&m).unwrap();
+    assert_eq!(Some(b"example.com".as_slice()), client_hello(h.body).unwrap().1.sni());
+    assert!(handshake_reassemble(&data[..data.len()-1]).unwrap_err().is_eod());
+}
@@ -0,0 +1,9 @@
+use parcelona::parser_combinators::{*};
+
+grammar! {
+    blank -> () = " "* => { } ;
+    pub blanks -> () = blank* => { } ;
+    pub items -> () = ("a"? "b"?)+ => { } ;
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use parcelona::parser_combinators::{*};
+
+grammar! {
+    sign -> () = "-"? => { } ;
+    pub sum -> () = sign sum "+" "1" => { } / "1" => { } ;
+    pub a -> () = b "x" => { } ;
+    b -> () = a "y" => { } / "z" => { } ;
+}
+
+fn main() {}
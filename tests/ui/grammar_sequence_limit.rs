@@ -0,0 +1,7 @@
+use parcelona::parser_combinators::{*};
+
+grammar! {
+    pub alphabet -> () = "a" "b" "c" "d" "e" "f" "g" "h" "i" "j" "k" "l" "m" "n" "o" "p" "q" => { } ;
+}
+
+fn main() {}
@@ -0,0 +1,119 @@
+use parcelona::parser_combinators::{*};
+use parcelona::uri::{*};
+
+fn s(x: Option<&[u8]>) -> Option<&str> { x.map(|x| std::str::from_utf8(x).unwrap()) }
+
+#[test]
+fn t_uri_components() {
+    // RFC 3986 3
+    let u = parse(b"foo://example.com:8042/over/there?name=ferret#nose").unwrap();
+    assert_eq!(Some("foo"), s(u.scheme));
+    assert_eq!(Some("example.com"), s(u.host()));
+    assert_eq!(Some(8042), u.port());
+    assert_eq!(b"/over/there", u.path);
+    assert_eq!(Some("name=ferret"), s(u.query));
+    assert_eq!(Some("nose"), s(u.fragment));
+    assert_eq!(vec![&b"over"[..], b"there"], u.path_segments().collect::<Vec<_>>());
+
+    let u = parse(b"urn:example:animal:ferret:nose").unwrap();
+    assert_eq!((Some("urn"), None), (s(u.scheme), u.authority));
+    assert_eq!(b"example:animal:ferret:nose", u.path);
+
+    let u = parse(b"https://user:pw@[2001:db8::7]:443/").unwrap();
+    let a = u.authority.unwrap();
+    assert_eq!(Some("user:pw"), s(a.userinfo));
+    assert_eq!(Host::IpLiteral(b"2001:db8::7"), a.host);
+    assert_eq!(Some(443), a.port);
+    assert_eq!("https://user:pw@[2001:db8::7]:443/", u.to_string());
+
+    let u = parse(b"//example.com").unwrap();
+    assert!(u.is_relative());
+    assert_eq!((Some("example.com"), &b""[..]), (s(u.host()), u.path));
+    assert_eq!(None, parse(b"http://a:/").unwrap().port());
+}
+
+#[test]
+fn t_uri_rfc_examples() {
+    // RFC 3986 1.1.2
+    for x in [
+        "ftp://ftp.is.co.za/rfc/rfc1808.txt",
+        "http://www.ietf.org/rfc/rfc2396.txt",
+        "ldap://[2001:db8::7]/c=GB?objectClass?one",
+        "mailto:John.Doe@example.com",
+        "news:comp.infosystems.www.servers.unix",
+        "tel:+1-816-555-1212",
+        "telnet://192.0.2.16:80/",
+        "urn:oasis:names:specification:docbook:dtd:xml:4.1.2",
+    ] {
+        let u = parse(x.as_bytes()).unwrap();
+        assert!(!u.is_relative(), "{x}");
+        assert_eq!(x, u.to_string());
+    }
+    let u = parse(b"ldap://[2001:db8::7]/c=GB?objectClass?one").unwrap();
+    assert_eq!(Some("objectClass?one"), s(u.query));
+    assert_eq!(Some("John.Doe@example.com"), parse(b"mailto:John.Doe@example.com").ok().and_then(|u| s(Some(u.path))));
+}
+
+#[test]
+fn t_uri_resolve() {
+    // RFC 3986 5.4
+    let base = parse(b"http://a/b/c/d;p?q").unwrap();
+    for (r, t) in [
+        ("g:h", "g:h"), ("g", "http://a/b/c/g"), ("./g", "http://a/b/c/g"), ("g/", "http://a/b/c/g/"),
+        ("/g", "http://a/g"), ("//g", "http://g"), ("?y", "http://a/b/c/d;p?y"), ("g?y", "http://a/b/c/g?y"),
+        ("#s", "http://a/b/c/d;p?q#s"), ("g#s", "http://a/b/c/g#s"), ("g?y#s", "http://a/b/c/g?y#s"),
+        (";x", "http://a/b/c/;x"), ("g;x", "http://a/b/c/g;x"), ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+        ("", "http://a/b/c/d;p?q"), (".", "http://a/b/c/"), ("./", "http://a/b/c/"), ("..", "http://a/b/"),
+        ("../", "http://a/b/"), ("../g", "http://a/b/g"), ("../..", "http://a/"), ("../../", "http://a/"),
+        ("../../g", "http://a/g"),
+        // abnormal
+        ("../../../g", "http://a/g"), ("../../../../g", "http://a/g"), ("/./g", "http://a/g"),
+        ("/../g", "http://a/g"), ("g.", "http://a/b/c/g."), (".g", "http://a/b/c/.g"), ("g..", "http://a/b/c/g.."),
+        ("..g", "http://a/b/c/..g"), ("./../g", "http://a/b/g"), ("./g/.", "http://a/b/c/g/"),
+        ("g/./h", "http://a/b/c/g/h"), ("g/../h", "http://a/b/c/h"), ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+        ("g;x=1/../y", "http://a/b/c/y"), ("g?y/./x", "http://a/b/c/g?y/./x"), ("g?y/../x", "http://a/b/c/g?y/../x"),
+        ("g#s/./x", "http://a/b/c/g#s/./x"), ("g#s/../x", "http://a/b/c/g#s/../x"), ("http:g", "http:g"),
+    ] {
+        let r = parse(r.as_bytes()).unwrap();
+        assert_eq!(t, String::from_utf8(base.resolve(&r)).unwrap(), "{r}");
+    }
+    assert_eq!(b"/a/g".to_vec(), remove_dot_segments(b"/a/b/c/./../../g"));
+    assert_eq!(b"mid/6".to_vec(), remove_dot_segments(b"mid/content=5/../6"));
+}
+
+#[test]
+fn t_uri_strict_lenient() {
+    let text = b"http://example.com/a|b{c}?q=\xd1\x8f end";
+    let (rest, u) = uri(Mode::Strict).parse(text).unwrap();
+    assert_eq!((&b"/a"[..], None), (u.path, u.query));
+    assert_eq!(b"|b{c}?q=\xd1\x8f end", rest);
+    let (rest, u) = uri(Mode::Lenient).parse(text).unwrap();
+    assert_eq!(b"/a|b{c}", u.path);
+    assert_eq!(Some(&b"q=\xd1\x8f"[..]), u.query);
+    assert_eq!(b" end", rest);
+
+    assert!(uri(Mode::Strict).parse(b"http://a/b%zz").is_err());
+    assert_eq!(b"/b%zz", uri(Mode::Lenient).parse(b"http://a/b%zz").unwrap().1.path);
+    assert!(parse(b"http://a:99999/").is_err());
+    assert!(parse(b"http://[::1/").is_err());
+    assert!(parse(b"http://[v1.fe80::a+en1]/").is_ok());
+    assert!(parse(b"http://[::ffff:192.0.2.1]:80/").is_ok());
+    for x in [&b"http://[:::::]/"[..], b"http://[1:2:3]/", b"http://[1::2::3]/", b"http://[::1.2.3]/", b"http://[1:2:3:4:5:6:7:8:9]/"] {
+        assert!(parse(x).is_err(), "{}", String::from_utf8_lossy(x));
+    }
+    assert!(parse(b"http://[zz]/").is_err());
+    assert!(uri(Mode::Lenient).parse(b"http://[zz]/").is_ok());
+    assert!(uri(Mode::Strict).parse(b"/relative").is_err());
+    assert!(uri_reference(Mode::Strict).parse(b"/relative").is_ok());
+}
+
+#[test]
+fn t_uri_percent() {
+    assert_eq!(b"a b/c".to_vec(), percent_decode(b"a%20b%2Fc").into_owned());
+    assert_eq!(b"100%".to_vec(), percent_decode(b"100%").into_owned());
+    assert!(matches!(percent_decode(b"plain"), std::borrow::Cow::Borrowed(_)));
+    assert_eq!(Some("я"), percent_decode_utf8(b"%D1%8F").as_deref());
+    assert_eq!(None, percent_decode_utf8(b"%FF"));
+    assert_eq!("a%20b%2Fc%D1%8F", percent_encode("a b/cя".as_bytes(), is_unreserved));
+    assert_eq!("a%20b/c", percent_encode(b"a b/c", |x| is_pchar(x) || *x == b'/'));
+}